@@ -0,0 +1,40 @@
+mod audit;
+mod cleanup;
+mod diff;
+mod duplicates;
+mod hygiene;
+mod library;
+mod lookup;
+mod media;
+mod portability;
+mod query;
+mod statistics;
+mod timestamps;
+mod usage;
+mod verify;
+pub use audit::{AuditEntry, AuditKind, AuditReport, Severity, audit};
+pub use cleanup::{CleanupCandidate, CleanupKind, CleanupReport, cleanup};
+pub use diff::{DiffEntry, DiffKind, DiffReport, DiffSide, content_diff, diff};
+pub use duplicates::{DuplicateCopy, DuplicateGroup, DuplicateReport, duplicates};
+pub use hygiene::{HygieneEntry, HygieneKind, HygieneReport, hygiene};
+pub use library::{Library, LibraryGroup, LibraryReport, LibraryScan, Location};
+pub use lookup::{LookupReport, lookup};
+pub use media::{MediaFolder, MediaReport, MediaTally, ProbeFailure, media};
+pub use portability::{PortabilityDir, PortabilityEntry, PortabilityKind, PortabilityReport, portability};
+pub use query::{QueryMatch, QueryReport, query};
+pub use statistics::{Ranked, Statistics, Tally, statistics};
+pub use timestamps::{AnomalyEntry, AnomalyKind, AnomalyReport, timestamps};
+pub use usage::{DiskUsage, UsageNode, disk_usage};
+pub use verify::{VerifyEntry, VerifyReport, VerifyStatus, verify};
+
+use std::{fs::File, path::Path};
+
+// The digest of empty content, and the Merkle hash of an empty directory
+pub const EMPTY_MD5: &str = "d41d8cd98f00b204e9800998ecf8427e";
+
+// Hashes like the scan does, without reading the whole file into memory
+fn file_md5(path: &Path) -> std::io::Result<String> {
+    let mut context = md5::Context::new();
+    std::io::copy(&mut File::open(path)?, &mut context)?;
+    Ok(format!("{:?}", context.finalize()))
+}
@@ -0,0 +1,158 @@
+use crate::record::TableReport;
+use crate::scan::{FileMode, FileType, Scan, SETGID, SETUID, STICKY};
+use serde::Serialize;
+use std::path::{Component, Path};
+
+const WORLD_WRITABLE: u32 = 0o002;
+const GROUP_WRITABLE: u32 = 0o020;
+// Directories at the top of the filesystem holding homes
+const HOMES: [&str; 2] = ["home", "Users"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+pub enum Severity {
+    Low,
+    Medium,
+    High,
+}
+
+impl Severity {
+    pub const ALL: [Severity; 3] = [Severity::High, Severity::Medium, Severity::Low];
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            Severity::Low => "Low",
+            Severity::Medium => "Medium",
+            Severity::High => "High",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+pub enum AuditKind {
+    WorldWritable,
+    MissingSticky,
+    Setuid,
+    Setgid,
+    UnknownOwner,
+    GroupWritableHome,
+}
+
+impl AuditKind {
+    pub fn title(&self) -> &'static str {
+        match self {
+            AuditKind::WorldWritable => "World-writable",
+            AuditKind::MissingSticky => "Missing sticky bit",
+            AuditKind::Setuid => "Setuid",
+            AuditKind::Setgid => "Setgid",
+            AuditKind::UnknownOwner => "Unknown owner",
+            AuditKind::GroupWritableHome => "Group-writable in a home",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AuditEntry {
+    pub severity: Severity,
+    pub kind: AuditKind,
+    pub id: usize,
+    pub path: String,
+    pub mode: String,
+    pub detail: String
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AuditReport {
+    pub entries: Vec<AuditEntry>,
+    // Items without permissions, every one for records before version 8
+    pub unknown: usize
+}
+
+impl AuditReport {
+    pub fn count(&self, severity: Severity) -> usize {
+        self.entries.iter().filter(|entry| entry.severity == severity).count()
+    }
+}
+
+impl TableReport for AuditReport {
+    const TITLES: &'static [&'static str] = &["Severity", "Kind", "Path", "Mode", "Detail"];
+
+    fn rows(&self) -> impl Iterator<Item = Vec<String>> + '_ {
+        self.entries.iter().map(|entry| vec![
+            entry.severity.title().to_owned(),
+            entry.kind.title().to_owned(),
+            entry.path.clone(),
+            entry.mode.clone(),
+            entry.detail.clone(),
+        ])
+    }
+}
+
+// Inside a home, or a home itself, only from the top so that a `home` directory in a project is not one
+fn in_home(path: &Path) -> bool {
+    let mut components = path.components().skip_while(|component| matches!(component, Component::Prefix(_)));
+    if components.next() != Some(Component::RootDir) {
+        return false;
+    }
+    match components.next() {
+        Some(Component::Normal(name)) if name == "root" => true,
+        Some(Component::Normal(name)) => HOMES.iter().any(|home| name == *home) && components.next().is_some(),
+        _ => false,
+    }
+}
+
+fn findings(mode: FileMode, file_type: &FileType, home: bool) -> Vec<(Severity, AuditKind, String)> {
+    let mut findings = Vec::new();
+    match file_type {
+        FileType::Dir => {
+            match (mode.mode & WORLD_WRITABLE != 0, mode.mode & STICKY != 0) {
+                (true, false) => findings.push((Severity::High, AuditKind::MissingSticky, "Anyone can delete or replace the files of others".to_owned())),
+                (true, true) => findings.push((Severity::Low, AuditKind::WorldWritable, "Sticky like /tmp".to_owned())),
+                (false, _) => (),
+            }
+            if home && mode.mode & GROUP_WRITABLE != 0 {
+                findings.push((Severity::Medium, AuditKind::GroupWritableHome, "The group can add or replace files".to_owned()));
+            }
+        }
+        FileType::Regular => {
+            if mode.mode & WORLD_WRITABLE != 0 {
+                findings.push((Severity::High, AuditKind::WorldWritable, "Anyone can change it".to_owned()));
+            }
+            if mode.mode & SETUID != 0 {
+                match mode.uid {
+                    0 => findings.push((Severity::High, AuditKind::Setuid, "Runs as root".to_owned())),
+                    uid => findings.push((Severity::Medium, AuditKind::Setuid, format!("Runs as uid {}", uid))),
+                }
+            }
+            if mode.mode & SETGID != 0 {
+                findings.push((Severity::Medium, AuditKind::Setgid, format!("Runs as gid {}", mode.gid)));
+            }
+            if home && mode.mode & GROUP_WRITABLE != 0 {
+                findings.push((Severity::Low, AuditKind::GroupWritableHome, "The group can change it".to_owned()));
+            }
+        }
+        // The mode of a symlink is the one of its target
+        FileType::Symlink => return findings,
+    }
+    if mode.orphan {
+        findings.push((Severity::Medium, AuditKind::UnknownOwner, format!("No user has uid {}", mode.uid)));
+    }
+    findings
+}
+
+pub fn audit(scan: &Scan) -> AuditReport {
+    let mut entries = Vec::new();
+    let mut unknown = 0;
+    for item in &scan.items {
+        let Some(mode) = item.mode() else {
+            unknown += 1;
+            continue;
+        };
+        let path = scan.path(item.id());
+        let home = in_home(&Path::new(&scan.root).join(&path));
+        for (severity, kind, detail) in findings(mode, &item.file_type(), home) {
+            entries.push(AuditEntry { severity, kind, id: item.id(), path: path.clone(), mode: mode.to_string(), detail });
+        }
+    }
+    entries.sort_by(|entrya, entryb| entryb.severity.cmp(&entrya.severity).then(entrya.id.cmp(&entryb.id)));
+    AuditReport { entries, unknown }
+}
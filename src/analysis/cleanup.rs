@@ -0,0 +1,155 @@
+use super::duplicates;
+use crate::record::TableReport;
+use crate::scan::{stringify_time, Scan, SpecificInfo};
+use serde::Serialize;
+
+const JUNK_NAMES: [&str; 4] = ["thumbs.db", "ehthumbs.db", "desktop.ini", ".ds_store"];
+// AppleDouble files and Office lock files
+const JUNK_PREFIXES: [&str; 2] = ["._", "~$"];
+const JUNK_EXTENSIONS: [&str; 4] = ["tmp", "temp", "swp", "crdownload"];
+const CACHE_DIRS: [&str; 7] = ["node_modules", "__pycache__", ".pytest_cache", ".mypy_cache", ".gradle", ".tox", ".sass-cache"];
+// Build outputs only next to their manifest, a `target` alone may be anything
+const BUILD_DIRS: [(&str, &str); 1] = [("target", "Cargo.toml")];
+// Neither modified nor accessed for this long, and large enough to matter
+const STALE: i64 = 2 * 365 * 24 * 3600 * 1_000_000_000;
+const STALE_SIZE: u64 = 16 << 20;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+pub enum CleanupKind {
+    Junk,
+    Cache,
+    Duplicate,
+    Stale,
+}
+
+impl CleanupKind {
+    pub const ALL: [CleanupKind; 4] = [CleanupKind::Junk, CleanupKind::Cache, CleanupKind::Duplicate, CleanupKind::Stale];
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            CleanupKind::Junk => "Junk files",
+            CleanupKind::Cache => "Build caches",
+            CleanupKind::Duplicate => "Duplicates",
+            CleanupKind::Stale => "Stale large files",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CleanupCandidate {
+    pub kind: CleanupKind,
+    pub id: usize,
+    pub path: String,
+    pub reclaimable: u64,
+    pub reason: String
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CleanupReport {
+    pub reclaimable: u64,
+    pub candidates: Vec<CleanupCandidate>
+}
+
+impl CleanupReport {
+    pub fn count(&self, kind: CleanupKind) -> usize {
+        self.candidates.iter().filter(|candidate| candidate.kind == kind).count()
+    }
+
+    pub fn reclaimable(&self, kind: CleanupKind) -> u64 {
+        self.candidates.iter().filter(|candidate| candidate.kind == kind).map(|candidate| candidate.reclaimable).sum()
+    }
+}
+
+impl TableReport for CleanupReport {
+    const TITLES: &'static [&'static str] = &["Kind", "Path", "Reclaimable", "Reason"];
+
+    fn rows(&self) -> impl Iterator<Item = Vec<String>> + '_ {
+        self.candidates.iter().map(|candidate| vec![
+            candidate.kind.title().to_owned(),
+            candidate.path.clone(),
+            candidate.reclaimable.to_string(),
+            candidate.reason.clone(),
+        ])
+    }
+}
+
+fn junk(name: &str) -> Option<String> {
+    let lowercase = name.to_lowercase();
+    if JUNK_NAMES.contains(&lowercase.as_str()) {
+        return Some(format!("System file {}", name));
+    }
+    if let Some(prefix) = JUNK_PREFIXES.iter().find(|prefix| name.starts_with(*prefix)) {
+        return Some(format!("Metadata or lock file {}*", prefix));
+    }
+    match lowercase.rsplit_once('.') {
+        Some((stem, extension)) if !stem.is_empty() && JUNK_EXTENSIONS.contains(&extension) => Some(format!("Temporary file *.{}", extension)),
+        _ => None,
+    }
+}
+
+// Every item is a candidate once at most, nothing inside a candidate directory is one again.
+// Only reports, the scanned tree is never touched.
+pub fn cleanup(scan: &Scan) -> CleanupReport {
+    let reference = scan.time_or_now().unwrap_or(i64::MAX);
+    let subtrees = scan.subtrees();
+    let mut candidates = Vec::new();
+    let mut covered = vec![false; scan.items.len()];
+    for item in &scan.items {
+        let id = item.id();
+        if item.parent().is_some_and(|parent| covered[parent]) {
+            covered[id] = true;
+            continue;
+        }
+        let reason = match item.info() {
+            SpecificInfo::Dir(_) if item.parent().is_some() => {
+                let siblings = || scan.items[scan.items[item.parent().unwrap()].childs().unwrap()].iter();
+                let build = BUILD_DIRS.iter().find(|(name, manifest)| item.name() == *name && siblings().any(|sibling| sibling.name() == *manifest));
+                match (CACHE_DIRS.contains(&item.name()), build) {
+                    (true, _) => Some((CleanupKind::Cache, format!("Cache of {} items", subtrees[id].count - 1))),
+                    (false, Some((_, manifest))) => Some((CleanupKind::Cache, format!("Build output next to {}", manifest))),
+                    (false, None) => None,
+                }
+            }
+            SpecificInfo::Regular(_) => junk(item.name()).map(|reason| (CleanupKind::Junk, reason)),
+            _ => None,
+        };
+        if let Some((kind, reason)) = reason {
+            covered[id] = true;
+            candidates.push(CleanupCandidate { kind, id, path: scan.path(id), reclaimable: subtrees[id].size, reason });
+        }
+    }
+    // The oldest copy is kept
+    for group in duplicates(scan).groups {
+        let mut copies: Vec<_> = group.copies.iter().filter(|copy| !covered[copy.id]).collect();
+        copies.sort_by_key(|copy| (scan.items[copy.id].times().modified, copy.id));
+        let Some((keep, extra)) = copies.split_first() else {
+            continue;
+        };
+        for copy in extra {
+            covered[copy.id] = true;
+            candidates.push(CleanupCandidate {
+                kind: CleanupKind::Duplicate,
+                id: copy.id,
+                path: copy.path.clone(),
+                reclaimable: group.size.unwrap_or_default(),
+                reason: format!("Same content as {}", keep.path)
+            });
+        }
+    }
+    for item in &scan.items {
+        let (id, times) = (item.id(), item.times());
+        if covered[id] || !matches!(item.info(), SpecificInfo::Regular(_)) || subtrees[id].size < STALE_SIZE {
+            continue;
+        }
+        let stale = |time: Option<i64>| time.is_none_or(|time| time < reference - STALE);
+        if times.modified.is_some() && stale(times.modified) && stale(times.accessed) {
+            let reason = match times.accessed {
+                Some(accessed) => format!("Modified {}, accessed {}", stringify_time(times.modified), stringify_time(Some(accessed))),
+                None => format!("Modified {}", stringify_time(times.modified)),
+            };
+            candidates.push(CleanupCandidate { kind: CleanupKind::Stale, id, path: scan.path(id), reclaimable: subtrees[id].size, reason });
+        }
+    }
+    candidates.sort_by(|candidatea, candidateb| candidateb.reclaimable.cmp(&candidatea.reclaimable).then(candidatea.id.cmp(&candidateb.id)));
+    CleanupReport { reclaimable: candidates.iter().map(|candidate| candidate.reclaimable).sum(), candidates }
+}
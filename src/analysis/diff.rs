@@ -0,0 +1,269 @@
+use crate::scan::{FileItem, Scan, SpecificInfo};
+use serde::Serialize;
+use super::EMPTY_MD5;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum DiffKind {
+    Added,
+    Removed,
+    Modified,
+    Renamed,
+    Moved,
+    MetadataOnly,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DiffSide {
+    pub id: usize,
+    pub path: String
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DiffEntry {
+    pub kind: DiffKind,
+    pub old: Option<DiffSide>,
+    pub new: Option<DiffSide>,
+    pub changes: Vec<&'static str>
+}
+
+impl DiffEntry {
+    pub fn path(&self) -> &str {
+        &self.new.as_ref().or(self.old.as_ref()).unwrap().path
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DiffReport {
+    pub entries: Vec<DiffEntry>,
+    // Number of changes under each directory, keyed by its path
+    pub rollups: BTreeMap<String, usize>
+}
+
+pub fn diff(old: &Scan, new: &Scan) -> DiffReport {
+    let old_paths: Vec<_> = (0..old.items.len()).map(|id| old.path(id)).collect();
+    let new_paths: Vec<_> = (0..new.items.len()).map(|id| new.path(id)).collect();
+    let old_index: HashMap<_, _> = old_paths.iter().enumerate().map(|(id, path)| (path.as_str(), id)).collect();
+    let new_index: HashMap<_, _> = new_paths.iter().enumerate().map(|(id, path)| (path.as_str(), id)).collect();
+    let side = |paths: &[String], id: usize| Some(DiffSide { id, path: paths[id].clone() });
+    let mut entries = Vec::new();
+    let mut removed = Vec::new();
+    for (old_id, path) in old_paths.iter().enumerate() {
+        match new_index.get(path.as_str()) {
+            None => removed.push(old_id),
+            Some(&new_id) => if let Some((kind, changes)) = compare(&old.items[old_id], &new.items[new_id]) {
+                entries.push(DiffEntry { kind, old: side(&old_paths, old_id), new: side(&new_paths, new_id), changes });
+            }
+        }
+    }
+    // A removed file and an added file with the same content are a rename or a move, but empty files all have the same content
+    let mut contents: HashMap<&str, Vec<usize>> = HashMap::new();
+    for &old_id in removed.iter().rev() {
+        if let Some(md5) = content_key(&old.items[old_id]) {
+            contents.entry(md5).or_default().push(old_id);
+        }
+    }
+    let mut matched = HashSet::new();
+    for new_id in (0..new.items.len()).filter(|new_id| !old_index.contains_key(new_paths[*new_id].as_str())) {
+        let candidates = content_key(&new.items[new_id]).and_then(|md5| contents.get_mut(md5)).filter(|candidates| !candidates.is_empty());
+        let Some(candidates) = candidates else {
+            entries.push(DiffEntry { kind: DiffKind::Added, old: None, new: side(&new_paths, new_id), changes: Vec::new() });
+            continue;
+        };
+        let name = new.items[new_id].name();
+        let dir = parent_path(&new_paths[new_id]);
+        let index = candidates.iter().position(|old_id| old.items[*old_id].name() == name)
+            .or_else(|| candidates.iter().position(|old_id| parent_path(&old_paths[*old_id]) == dir))
+            .unwrap_or(candidates.len() - 1);
+        let old_id = candidates.remove(index);
+        matched.insert(old_id);
+        let kind = match parent_path(&old_paths[old_id]) == dir {
+            true => DiffKind::Renamed,
+            false => DiffKind::Moved,
+        };
+        entries.push(DiffEntry { kind, old: side(&old_paths, old_id), new: side(&new_paths, new_id), changes: Vec::new() });
+    }
+    for old_id in removed.into_iter().filter(|old_id| !matched.contains(old_id)) {
+        entries.push(DiffEntry { kind: DiffKind::Removed, old: side(&old_paths, old_id), new: None, changes: Vec::new() });
+    }
+    report(entries)
+}
+
+// Only content changes, descending only into directories whose Merkle hashes differ, so unchanged subtrees are skipped.
+// Renames and moves are a removal and an addition, and a removed or added directory is one entry.
+pub fn content_diff(old: &Scan, new: &Scan) -> DiffReport {
+    let mut entries = Vec::new();
+    let mut dirs = vec![(0, 0, String::new())];
+    while let Some((old_dir, new_dir, prefix)) = dirs.pop() {
+        let (old_dir, new_dir) = (&old.items[old_dir], &new.items[new_dir]);
+        if old_dir.dir_hash().is_some() && old_dir.dir_hash() == new_dir.dir_hash() {
+            continue;
+        }
+        let childs = |scan: &Scan, dir: &FileItem| -> HashMap<String, usize> {
+            dir.childs().map(|childs| scan.items[childs].iter().map(|item| (item.name().to_owned(), item.id())).collect()).unwrap_or_default()
+        };
+        let (old_childs, new_childs) = (childs(old, old_dir), childs(new, new_dir));
+        let side = |id: usize, name: &str| Some(DiffSide { id, path: prefix.clone() + name });
+        for (name, &old_id) in &old_childs {
+            let Some(&new_id) = new_childs.get(name) else {
+                entries.push(DiffEntry { kind: DiffKind::Removed, old: side(old_id, name), new: None, changes: Vec::new() });
+                continue;
+            };
+            let (old_item, new_item) = (&old.items[old_id], &new.items[new_id]);
+            let changes = match (old_item.childs(), new_item.childs()) {
+                _ if old_item.file_type() != new_item.file_type() => vec!["type"],
+                (Some(_), Some(_)) => {
+                    dirs.push((old_id, new_id, prefix.clone() + name + "/"));
+                    continue;
+                }
+                (None, Some(_)) | (Some(_), None) => vec!["access"],
+                (None, None) if content(old_item) != content(new_item) => vec!["hash"],
+                (None, None) => continue,
+            };
+            entries.push(DiffEntry { kind: DiffKind::Modified, old: side(old_id, name), new: side(new_id, name), changes });
+        }
+        for (name, &new_id) in new_childs.iter().filter(|(name, _)| !old_childs.contains_key(*name)) {
+            entries.push(DiffEntry { kind: DiffKind::Added, old: None, new: side(new_id, name), changes: Vec::new() });
+        }
+    }
+    report(entries)
+}
+
+fn report(mut entries: Vec<DiffEntry>) -> DiffReport {
+    entries.sort_by(|entrya, entryb| entrya.path().cmp(entryb.path()));
+    let mut rollups = BTreeMap::new();
+    for entry in &entries {
+        let path = entry.path();
+        *rollups.entry(".".to_owned()).or_default() += 1;
+        for (i, _) in path.match_indices('/') {
+            *rollups.entry(path[..i].to_owned()).or_default() += 1;
+        }
+    }
+    DiffReport { entries, rollups }
+}
+
+fn compare(old: &FileItem, new: &FileItem) -> Option<(DiffKind, Vec<&'static str>)> {
+    if old.file_type() != new.file_type() {
+        return Some((DiffKind::Modified, vec!["type"]));
+    }
+    // The times of a directory change with its content, which is reported by itself
+    if old.is_dir() {
+        return None;
+    }
+    let mut changes = Vec::new();
+    if old.md5() != new.md5() {
+        changes.push("hash");
+    }
+    if old.size().zip(new.size()).is_some_and(|(old, new)| old != new) {
+        changes.push("size");
+    }
+    let content_changed = !changes.is_empty();
+    if old.times().modified != new.times().modified {
+        changes.push("modified time");
+    }
+    if old.times().created != new.times().created {
+        changes.push("created time");
+    }
+    match (content_changed, changes.is_empty()) {
+        (true, _) => Some((DiffKind::Modified, changes)),
+        (false, false) => Some((DiffKind::MetadataOnly, changes)),
+        (false, true) => None,
+    }
+}
+
+// What a file or a link holds
+fn content(item: &FileItem) -> Option<&str> {
+    match item.info() {
+        SpecificInfo::Regular(regular) => Some(&regular.md5),
+        SpecificInfo::Symlink(symlink) => symlink.link.as_deref(),
+        _ => None,
+    }
+}
+
+fn content_key(item: &FileItem) -> Option<&str> {
+    match item.info() {
+        SpecificInfo::Regular(regular) if regular.md5 != EMPTY_MD5 && regular.size != Some(0) => Some(&regular.md5),
+        _ => None,
+    }
+}
+
+fn parent_path(path: &str) -> &str {
+    path.rsplit_once('/').map(|(parent, _)| parent).unwrap_or(".")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scan::{DirInfo, FileTimes, RegularInfo};
+
+    // Items by name, parent and md5, breadth first, directories have no md5
+    fn scan(items: &[(&str, Option<usize>, Option<&str>, i64)]) -> Scan {
+        let items = items.iter().enumerate().map(|(id, &(name, parent, md5, modified))| {
+            let info = match md5 {
+                None => {
+                    let childs: Vec<_> = items.iter().enumerate().filter(|(_, item)| item.1 == Some(id)).map(|(id, _)| id).collect();
+                    let (start, end) = childs.first().zip(childs.last()).map_or((items.len(), items.len() - 1), |(start, end)| (*start, *end));
+                    SpecificInfo::Dir(DirInfo::new(start, end, false))
+                }
+                Some(md5) => {
+                    let size = Some(if md5 == EMPTY_MD5 { 0 } else { 1 });
+                    SpecificInfo::Regular(RegularInfo { md5: md5.to_owned(), size, inode: None, metas: Vec::new(), media: None, probe_error: None })
+                }
+            };
+            FileItem::new(id, name.to_owned(), parent, info, FileTimes { modified: Some(modified), ..Default::default() }, None)
+        }).collect();
+        Scan { items, root: String::new(), description: String::new(), warning: String::new(), time: None }
+    }
+
+    fn kinds(report: &DiffReport) -> Vec<(DiffKind, Option<&str>, Option<&str>)> {
+        report.entries.iter().map(|entry| (entry.kind, entry.old.as_ref().map(|old| old.path.as_str()), entry.new.as_ref().map(|new| new.path.as_str()))).collect()
+    }
+
+    #[test]
+    fn renames_and_moves() {
+        let old = scan(&[
+            ("", None, None, 0), ("a", Some(0), None, 0), ("b", Some(0), None, 0), ("kept", Some(0), Some("1"), 0),
+            ("empty", Some(0), Some(EMPTY_MD5), 0), ("gone", Some(0), Some("4"), 0), ("x", Some(1), Some("2"), 0), ("y", Some(1), Some("3"), 0),
+        ]);
+        let new = scan(&[
+            ("", None, None, 0), ("a", Some(0), None, 0), ("b", Some(0), None, 0), ("kept", Some(0), Some("1"), 1),
+            ("blank", Some(0), Some(EMPTY_MD5), 0), ("fresh", Some(0), Some("5"), 0), ("renamed", Some(1), Some("2"), 0), ("y", Some(2), Some("3"), 0),
+        ]);
+        assert_eq!(kinds(&diff(&old, &new)), [
+            (DiffKind::Renamed, Some("a/x"), Some("a/renamed")),
+            (DiffKind::Moved, Some("a/y"), Some("b/y")),
+            // Empty files all have the same content, so they are never taken for a rename
+            (DiffKind::Added, None, Some("blank")),
+            (DiffKind::Removed, Some("empty"), None),
+            (DiffKind::Added, None, Some("fresh")),
+            (DiffKind::Removed, Some("gone"), None),
+            (DiffKind::MetadataOnly, Some("kept"), Some("kept")),
+        ]);
+        // Moves are counted where they went
+        let rollups = diff(&old, &new).rollups;
+        assert_eq!((rollups.get("."), rollups.get("a"), rollups.get("b")), (Some(&7), Some(&1), Some(&1)));
+    }
+
+    #[test]
+    fn copies() {
+        // Of removed files with the same content, the one with the same name and then the one in the same directory is matched
+        let old = scan(&[("", None, None, 0), ("a", Some(0), None, 0), ("b", Some(0), None, 0), ("one", Some(1), Some("1"), 0), ("two", Some(2), Some("1"), 0)]);
+        let new = scan(&[("", None, None, 0), ("a", Some(0), None, 0), ("b", Some(0), None, 0), ("two", Some(1), Some("1"), 0), ("three", Some(2), Some("1"), 0)]);
+        assert_eq!(kinds(&diff(&old, &new)), [
+            (DiffKind::Moved, Some("b/two"), Some("a/two")),
+            (DiffKind::Moved, Some("a/one"), Some("b/three")),
+        ]);
+        let new = scan(&[("", None, None, 0), ("a", Some(0), None, 0), ("b", Some(0), None, 0), ("three", Some(1), Some("1"), 0)]);
+        assert_eq!(kinds(&diff(&old, &new)), [(DiffKind::Renamed, Some("a/one"), Some("a/three")), (DiffKind::Removed, Some("b/two"), None)]);
+    }
+
+    #[test]
+    fn modified() {
+        let old = scan(&[("", None, None, 0), ("file", Some(0), Some("1"), 0), ("dir", Some(0), None, 0)]);
+        let new = scan(&[("", None, None, 5), ("file", Some(0), Some("2"), 1), ("dir", Some(0), Some("3"), 0)]);
+        let report = diff(&old, &new);
+        assert_eq!(kinds(&report), [(DiffKind::Modified, Some("dir"), Some("dir")), (DiffKind::Modified, Some("file"), Some("file"))]);
+        assert_eq!(report.entries[0].changes, ["type"]);
+        assert_eq!(report.entries[1].changes, ["hash", "modified time"]);
+    }
+}
@@ -0,0 +1,61 @@
+use super::EMPTY_MD5;
+use crate::record::TableReport;
+use crate::scan::{Scan, SpecificInfo};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DuplicateCopy {
+    pub id: usize,
+    pub path: String
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DuplicateGroup {
+    pub md5: String,
+    pub size: Option<u64>,
+    pub wasted: u64,
+    pub copies: Vec<DuplicateCopy>
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DuplicateReport {
+    pub wasted: u64,
+    pub groups: Vec<DuplicateGroup>
+}
+
+impl TableReport for DuplicateReport {
+    const TITLES: &'static [&'static str] = &["Group", "MD5", "Size", "Wasted", "Path"];
+
+    fn rows(&self) -> impl Iterator<Item = Vec<String>> + '_ {
+        self.groups.iter().enumerate().flat_map(|(i, group)| group.copies.iter().map(move |copy| vec![
+            (i + 1).to_string(),
+            group.md5.clone(),
+            group.size.map(|size| size.to_string()).unwrap_or_default(),
+            group.wasted.to_string(),
+            copy.path.clone(),
+        ]))
+    }
+}
+
+// Groups regular files with the same size and content, ranked by the space taken by the extra copies.
+// Empty files are not duplicates worth anything, and hard links of one file are only one copy.
+pub fn duplicates(scan: &Scan) -> DuplicateReport {
+    let mut contents: HashMap<(Option<u64>, &str), Vec<usize>> = HashMap::new();
+    for item in &scan.items {
+        if let SpecificInfo::Regular(regular) = item.info() && regular.size != Some(0) && regular.md5 != EMPTY_MD5 {
+            contents.entry((regular.size, &regular.md5)).or_default().push(item.id());
+        }
+    }
+    let mut groups: Vec<_> = contents.into_iter().filter_map(|((size, md5), ids)| {
+        let mut inodes = HashSet::new();
+        let copies: Vec<_> = ids.into_iter()
+            .filter(|id| scan.items[*id].inode().is_none_or(|inode| inodes.insert(inode)))
+            .map(|id| DuplicateCopy { id, path: scan.path(id) })
+            .collect();
+        let wasted = size.unwrap_or_default() * (copies.len() as u64).saturating_sub(1);
+        (copies.len() > 1).then(|| DuplicateGroup { md5: md5.to_owned(), size, wasted, copies })
+    }).collect();
+    groups.sort_by(|groupa, groupb| groupb.wasted.cmp(&groupa.wasted).then_with(|| groupb.copies.len().cmp(&groupa.copies.len())).then_with(|| groupa.md5.cmp(&groupb.md5)));
+    DuplicateReport { wasted: groups.iter().map(|group| group.wasted).sum(), groups }
+}
@@ -0,0 +1,111 @@
+use crate::record::TableReport;
+use crate::scan::{Scan, SpecificInfo};
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+pub enum HygieneKind {
+    EmptyDir,
+    ZeroByte,
+    BrokenLink,
+    ExternalLink,
+    Inaccessible,
+    Truncated,
+}
+
+impl HygieneKind {
+    pub const ALL: [HygieneKind; 6] = [
+        HygieneKind::EmptyDir,
+        HygieneKind::ZeroByte,
+        HygieneKind::BrokenLink,
+        HygieneKind::ExternalLink,
+        HygieneKind::Inaccessible,
+        HygieneKind::Truncated,
+    ];
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            HygieneKind::EmptyDir => "Empty directories",
+            HygieneKind::ZeroByte => "Zero-byte files",
+            HygieneKind::BrokenLink => "Broken symlinks",
+            HygieneKind::ExternalLink => "External symlinks",
+            HygieneKind::Inaccessible => "Inaccessible",
+            HygieneKind::Truncated => "Truncated directories",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HygieneEntry {
+    pub kind: HygieneKind,
+    pub id: usize,
+    pub path: String,
+    pub detail: String
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HygieneReport {
+    pub entries: Vec<HygieneEntry>
+}
+
+impl HygieneReport {
+    pub fn count(&self, kind: HygieneKind) -> usize {
+        self.entries.iter().filter(|entry| entry.kind == kind).count()
+    }
+}
+
+impl TableReport for HygieneReport {
+    const TITLES: &'static [&'static str] = &["Kind", "Path", "Detail"];
+
+    fn rows(&self) -> impl Iterator<Item = Vec<String>> + '_ {
+        self.entries.iter().map(|entry| vec![entry.kind.title().to_owned(), entry.path.clone(), entry.detail.clone()])
+    }
+}
+
+pub fn hygiene(scan: &Scan) -> HygieneReport {
+    // Empty directories hold nothing but empty directories, children are done before their parent.
+    // Only the outermost one of nested empty directories is reported.
+    let mut empty = vec![false; scan.items.len()];
+    for item in scan.items.iter().rev() {
+        if let SpecificInfo::Dir(dir) = item.info() {
+            empty[item.id()] = !dir.truncated && dir.childs.clone().all(|child| empty[child]);
+        }
+    }
+    let subtrees = scan.subtrees();
+    let mut entries = Vec::new();
+    let mut push = |kind, id, detail: String| entries.push(HygieneEntry { kind, id, path: scan.path(id), detail });
+    for item in &scan.items {
+        let id = item.id();
+        match item.info() {
+            SpecificInfo::Dir(dir) => {
+                if empty[id] && item.parent().is_none_or(|parent| !empty[parent]) {
+                    match subtrees[id].count - 1 {
+                        0 => push(HygieneKind::EmptyDir, id, "No entries".to_owned()),
+                        count => push(HygieneKind::EmptyDir, id, format!("Only {} empty directories inside", count)),
+                    }
+                }
+                if dir.truncated {
+                    push(HygieneKind::Truncated, id, "Entries left out as the scan limit was reached".to_owned());
+                }
+            }
+            SpecificInfo::Regular(regular) => {
+                if regular.size == Some(0) {
+                    push(HygieneKind::ZeroByte, id, String::new());
+                }
+            }
+            SpecificInfo::Symlink(symlink) => {
+                let link = symlink.link.clone().unwrap_or_default();
+                match (symlink.broken, symlink.target) {
+                    (Some(true), _) => push(HygieneKind::BrokenLink, id, link),
+                    (Some(false), None) => push(HygieneKind::ExternalLink, id, link),
+                    // Older records can not tell a broken link from an external one
+                    (None, None) => push(HygieneKind::BrokenLink, id, "Broken or external".to_owned()),
+                    (_, Some(_)) => (),
+                }
+            }
+            SpecificInfo::Inaccessible(inaccessible) => {
+                push(HygieneKind::Inaccessible, id, inaccessible.reason.clone().unwrap_or("Unknown reason".to_owned()));
+            }
+        }
+    }
+    HygieneReport { entries }
+}
@@ -0,0 +1,105 @@
+use super::EMPTY_MD5;
+use crate::record::{self, Catalog, RecordError, TableReport};
+use crate::scan::{Scan, SpecificInfo};
+use serde::Serialize;
+use std::{collections::HashMap, path::Path};
+
+pub struct LibraryScan {
+    pub name: String,
+    pub scan: Scan
+}
+
+// Scans of many drives, indexed by content
+#[derive(Default)]
+pub struct Library {
+    pub scans: Vec<LibraryScan>,
+    contents: HashMap<String, Vec<(usize, usize)>>
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Location {
+    pub scan: String,
+    pub root: String,
+    pub path: String,
+    pub created: Option<i64>,
+    pub modified: Option<i64>
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LibraryGroup {
+    pub md5: String,
+    pub size: Option<u64>,
+    pub locations: Vec<Location>
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LibraryReport {
+    pub groups: Vec<LibraryGroup>
+}
+
+impl TableReport for LibraryReport {
+    const TITLES: &'static [&'static str] = &["Group", "MD5", "Size", "Scan", "Root", "Path"];
+
+    fn rows(&self) -> impl Iterator<Item = Vec<String>> + '_ {
+        self.groups.iter().enumerate().flat_map(|(i, group)| group.locations.iter().map(move |location| vec![
+            (i + 1).to_string(),
+            group.md5.clone(),
+            group.size.map(|size| size.to_string()).unwrap_or_default(),
+            location.scan.clone(),
+            location.root.clone(),
+            location.path.clone(),
+        ]))
+    }
+}
+
+impl Library {
+    // Every scan of a catalog is added
+    pub fn add(&mut self, path: &Path) -> Result<(), RecordError> {
+        let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+        if record::is_catalog(path) {
+            let catalog = Catalog::open(path)?;
+            for scan in catalog.scans()? {
+                let name = format!("{} #{}", file_name, scan.id);
+                self.push(name, catalog.load(scan.id)?);
+            }
+        } else {
+            self.push(file_name.into_owned(), record::load(path)?);
+        }
+        Ok(())
+    }
+
+    fn push(&mut self, name: String, scan: Scan) {
+        let index = self.scans.len();
+        for item in &scan.items {
+            if let SpecificInfo::Regular(regular) = item.info() && regular.size != Some(0) && regular.md5 != EMPTY_MD5 {
+                self.contents.entry(regular.md5.clone()).or_default().push((index, item.id()));
+            }
+        }
+        self.scans.push(LibraryScan { name, scan });
+    }
+
+    pub fn locations(&self, md5: &str) -> Option<LibraryGroup> {
+        let (md5, found) = self.contents.get_key_value(&md5.to_ascii_lowercase())?;
+        let locations = found.iter().map(|&(index, id)| self.location(index, id)).collect();
+        let size = found.iter().find_map(|&(index, id)| self.scans[index].scan.items[id].size());
+        Some(LibraryGroup { md5: md5.clone(), size, locations })
+    }
+
+    pub fn location(&self, index: usize, id: usize) -> Location {
+        let LibraryScan { name, scan } = &self.scans[index];
+        let times = scan.items[id].times();
+        Location { scan: name.clone(), root: scan.root.clone(), path: scan.path(id), created: times.created, modified: times.modified }
+    }
+
+    // Contents found at more than one place, the ones on the most scans first
+    pub fn duplicates(&self) -> LibraryReport {
+        let mut found: Vec<_> = self.contents.iter().filter(|(_, found)| found.len() > 1).collect();
+        let scan_count = |found: &[(usize, usize)]| {
+            let mut scans: Vec<_> = found.iter().map(|(index, _)| *index).collect();
+            scans.dedup();
+            scans.len()
+        };
+        found.sort_by(|(md5a, founda), (md5b, foundb)| scan_count(foundb).cmp(&scan_count(founda)).then_with(|| md5a.cmp(md5b)));
+        LibraryReport { groups: found.into_iter().filter_map(|(md5, _)| self.locations(md5)).collect() }
+    }
+}
@@ -0,0 +1,46 @@
+use super::{Library, Location, file_md5};
+use crate::record::TableReport;
+use crate::scan::{FileType, stringify_time};
+use serde::Serialize;
+use std::path::Path;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LookupReport {
+    pub path: String,
+    pub md5: String,
+    pub matches: Vec<Location>,
+    // Items with the same name but another content, when nothing matches the content
+    pub same_name: Vec<Location>
+}
+
+impl TableReport for LookupReport {
+    const TITLES: &'static [&'static str] = &["Match", "Scan", "Root", "Path", "Created", "Modified"];
+
+    fn rows(&self) -> impl Iterator<Item = Vec<String>> + '_ {
+        let matches = self.matches.iter().map(|location| ("Same content", location));
+        let same_name = self.same_name.iter().map(|location| ("Same name", location));
+        matches.chain(same_name).map(|(kind, location)| vec![
+            kind.to_owned(),
+            location.scan.clone(),
+            location.root.clone(),
+            location.path.clone(),
+            stringify_time(location.created),
+            stringify_time(location.modified),
+        ])
+    }
+}
+
+pub fn lookup(library: &Library, path: &Path) -> std::io::Result<LookupReport> {
+    let md5 = file_md5(path)?;
+    let matches = library.locations(&md5).map(|group| group.locations).unwrap_or_default();
+    let mut same_name = Vec::new();
+    if matches.is_empty() {
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        for (index, scan) in library.scans.iter().enumerate() {
+            for item in scan.scan.items.iter().filter(|item| item.file_type() == FileType::Regular && item.name() == name) {
+                same_name.push(library.location(index, item.id()));
+            }
+        }
+    }
+    Ok(LookupReport { path: path.to_string_lossy().into_owned(), md5, matches, same_name })
+}
@@ -0,0 +1,131 @@
+use super::statistics::category;
+use crate::scan::{Scan, SpecificInfo};
+use serde::Serialize;
+use std::collections::HashMap;
+
+// By the shorter side, so that portrait videos get the same class
+const RESOLUTIONS: [(u32, &str); 5] = [(2160, "2160p and above"), (1440, "1440p"), (1080, "1080p"), (720, "720p"), (480, "480p")];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MediaTally {
+    pub name: String,
+    pub count: u64,
+    // In microseconds, like every duration
+    pub duration: i64
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MediaFolder {
+    pub id: usize,
+    pub path: String,
+    pub count: u64,
+    pub duration: i64
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProbeFailure {
+    pub id: usize,
+    pub path: String,
+    pub error: String
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MediaReport {
+    pub count: u64,
+    pub duration: i64,
+    // Totals of the whole subtree, the longest first
+    pub folders: Vec<MediaFolder>,
+    pub video_codecs: Vec<MediaTally>,
+    pub audio_codecs: Vec<MediaTally>,
+    pub resolutions: Vec<MediaTally>,
+    // Audio only files by their artist and album tags
+    pub albums: Vec<MediaTally>,
+    // Audio and video files by their extension, ffmpeg fails on any other file too
+    pub failures: Vec<ProbeFailure>,
+    pub other_failures: u64
+}
+
+fn add(tallies: &mut HashMap<String, MediaTally>, name: String, duration: i64) {
+    let tally = tallies.entry(name.clone()).or_insert(MediaTally { name, count: 0, duration: 0 });
+    tally.count += 1;
+    tally.duration += duration;
+}
+
+fn sorted(tallies: HashMap<String, MediaTally>) -> Vec<MediaTally> {
+    let mut tallies: Vec<_> = tallies.into_values().collect();
+    tallies.sort_by(|tallya, tallyb| tallyb.count.cmp(&tallya.count).then_with(|| tallya.name.cmp(&tallyb.name)));
+    tallies
+}
+
+fn tag<'a>(metas: &'a [(String, String)], keys: &[&str]) -> Option<&'a str> {
+    keys.iter().find_map(|key| metas.iter().find(|(name, _)| name.eq_ignore_ascii_case(key))).map(|(_, value)| value.as_str())
+}
+
+pub fn media(scan: &Scan) -> MediaReport {
+    let (mut video_codecs, mut audio_codecs, mut resolutions, mut albums) = Default::default();
+    let (mut count, mut duration, mut failures, mut other_failures) = (0, 0, Vec::new(), 0);
+    // Folders sum up everything inside, children are done before their parent
+    let mut folders = vec![(0, 0); scan.items.len()];
+    for item in scan.items.iter().rev() {
+        let (id, parent) = (item.id(), item.parent());
+        let SpecificInfo::Regular(regular) = item.info() else {
+            if let Some(parent) = parent {
+                folders[parent].0 += folders[id].0;
+                folders[parent].1 += folders[id].1;
+            }
+            continue;
+        };
+        if let Some(error) = &regular.probe_error {
+            match category(item.name()) {
+                "Video" | "Audio" => failures.push(ProbeFailure { id, path: scan.path(id), error: error.clone() }),
+                _ => other_failures += 1,
+            }
+        }
+        let Some(media) = &regular.media else {
+            continue;
+        };
+        if media.video_codec.is_none() && media.audio_codec.is_none() {
+            continue;
+        }
+        let length = media.duration.unwrap_or_default();
+        count += 1;
+        duration += length;
+        if let Some(parent) = parent {
+            folders[parent].0 += 1;
+            folders[parent].1 += length;
+        }
+        if let Some(codec) = &media.video_codec {
+            add(&mut video_codecs, codec.clone(), length);
+            let class = match media.width.zip(media.height).map(|(width, height)| width.min(height)) {
+                None => "Unknown",
+                Some(side) => RESOLUTIONS.iter().find(|(least, _)| side >= *least).map_or("Below 480p", |(_, class)| class),
+            };
+            add(&mut resolutions, class.to_owned(), length);
+        }
+        if let Some(codec) = &media.audio_codec {
+            add(&mut audio_codecs, codec.clone(), length);
+            if media.video_codec.is_none() {
+                let artist = tag(&regular.metas, &["artist", "album_artist"]).unwrap_or("Unknown artist");
+                let album = tag(&regular.metas, &["album"]).unwrap_or("Unknown album");
+                add(&mut albums, format!("{} / {}", artist, album), length);
+            }
+        }
+    }
+    let mut folders: Vec<_> = folders.into_iter().enumerate()
+        .filter(|(id, (count, _))| *count > 0 && scan.items[*id].is_dir())
+        .map(|(id, (count, duration))| MediaFolder { id, path: scan.path(id), count, duration })
+        .collect();
+    folders.sort_by(|foldera, folderb| folderb.duration.cmp(&foldera.duration).then(foldera.id.cmp(&folderb.id)));
+    failures.reverse();
+    MediaReport {
+        count,
+        duration,
+        folders,
+        video_codecs: sorted(video_codecs),
+        audio_codecs: sorted(audio_codecs),
+        resolutions: sorted(resolutions),
+        albums: sorted(albums),
+        failures,
+        other_failures
+    }
+}
@@ -0,0 +1,176 @@
+use crate::record::TableReport;
+use crate::scan::{Scan, SpecificInfo};
+use serde::Serialize;
+use std::collections::HashMap;
+use unicode_normalization::UnicodeNormalization;
+
+const FORBIDDEN: [char; 9] = ['<', '>', ':', '"', '/', '\\', '|', '?', '*'];
+// Reserved with any extension, like `nul.txt`
+const RESERVED: [&str; 22] = [
+    "CON", "PRN", "AUX", "NUL",
+    "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8", "COM9",
+    "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+// MAX_PATH of Windows counts UTF-16 units, PATH_MAX of Linux counts bytes.
+// Paths are relative to the scan root, the destination adds its own length.
+const WINDOWS_PATH: usize = 260;
+const LINUX_PATH: usize = 4096;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+pub enum PortabilityKind {
+    ForbiddenChar,
+    ReservedName,
+    TrailingDotSpace,
+    LongPath,
+    CaseCollision,
+    NormalizationCollision,
+}
+
+impl PortabilityKind {
+    pub const ALL: [PortabilityKind; 6] = [
+        PortabilityKind::ForbiddenChar,
+        PortabilityKind::ReservedName,
+        PortabilityKind::TrailingDotSpace,
+        PortabilityKind::LongPath,
+        PortabilityKind::CaseCollision,
+        PortabilityKind::NormalizationCollision,
+    ];
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            PortabilityKind::ForbiddenChar => "Forbidden characters",
+            PortabilityKind::ReservedName => "Reserved names",
+            PortabilityKind::TrailingDotSpace => "Trailing dots and spaces",
+            PortabilityKind::LongPath => "Long paths",
+            PortabilityKind::CaseCollision => "Case collisions",
+            PortabilityKind::NormalizationCollision => "Normalization collisions",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PortabilityEntry {
+    pub kind: PortabilityKind,
+    pub id: usize,
+    pub name: String,
+    pub detail: String
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PortabilityDir {
+    pub id: usize,
+    pub path: String,
+    pub entries: Vec<PortabilityEntry>
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PortabilityReport {
+    pub dirs: Vec<PortabilityDir>
+}
+
+impl PortabilityReport {
+    pub fn count(&self, kind: PortabilityKind) -> usize {
+        self.dirs.iter().flat_map(|dir| &dir.entries).filter(|entry| entry.kind == kind).count()
+    }
+}
+
+impl TableReport for PortabilityReport {
+    const TITLES: &'static [&'static str] = &["Directory", "Kind", "Name", "Detail"];
+
+    fn rows(&self) -> impl Iterator<Item = Vec<String>> + '_ {
+        self.dirs.iter().flat_map(|dir| dir.entries.iter().map(|entry| vec![
+            dir.path.clone(),
+            entry.kind.title().to_owned(),
+            entry.name.clone(),
+            entry.detail.clone(),
+        ]))
+    }
+}
+
+fn name_problems(name: &str) -> Vec<(PortabilityKind, String)> {
+    let mut problems = Vec::new();
+    let mut forbidden: Vec<_> = name.chars().filter(|char| FORBIDDEN.contains(char) || char.is_ascii_control()).collect();
+    forbidden.dedup();
+    if !forbidden.is_empty() {
+        problems.push((PortabilityKind::ForbiddenChar, format!("{:?} not allowed on Windows", forbidden)));
+    }
+    let stem = name.split('.').next().unwrap_or_default().trim_end_matches(' ');
+    if let Some(reserved) = RESERVED.iter().find(|reserved| stem.eq_ignore_ascii_case(reserved)) {
+        problems.push((PortabilityKind::ReservedName, format!("{} is reserved on Windows", reserved)));
+    }
+    if name.ends_with(['.', ' ']) {
+        problems.push((PortabilityKind::TrailingDotSpace, "Windows drops them".to_owned()));
+    }
+    problems
+}
+
+// Siblings having the same key collide, naming the others for each of them
+fn collisions<'a>(names: &[&'a str], key: impl Fn(&str) -> String) -> Vec<(usize, Vec<&'a str>)> {
+    let mut groups: HashMap<String, Vec<usize>> = HashMap::new();
+    for (i, name) in names.iter().enumerate() {
+        groups.entry(key(name)).or_default().push(i);
+    }
+    let mut collisions: Vec<_> = groups.into_values().filter(|group| group.len() > 1).flat_map(|group| {
+        group.iter().map(|&i| (i, group.iter().filter(|&&j| j != i).map(|&j| names[j]).collect())).collect::<Vec<_>>()
+    }).collect();
+    collisions.sort_by_key(|(i, _)| *i);
+    collisions
+}
+
+pub fn portability(scan: &Scan) -> PortabilityReport {
+    let mut dirs = Vec::new();
+    for dir in &scan.items {
+        let SpecificInfo::Dir(info) = dir.info() else {
+            continue;
+        };
+        let childs = &scan.items[info.childs.clone()];
+        let names: Vec<_> = childs.iter().map(|child| child.name()).collect();
+        let mut entries = Vec::new();
+        let dir_path = match dir.parent() {
+            Some(_) => scan.path(dir.id()) + "/",
+            None => String::new(),
+        };
+        // Only where paths get too long, everything inside is too long as well
+        let dir_long = dir_path.encode_utf16().count() > WINDOWS_PATH + 1 || dir_path.len() > LINUX_PATH + 1;
+        for child in childs {
+            for (kind, detail) in name_problems(child.name()) {
+                entries.push(PortabilityEntry { kind, id: child.id(), name: child.name().to_owned(), detail });
+            }
+            let path = dir_path.clone() + child.name();
+            let (units, bytes) = (path.encode_utf16().count(), path.len());
+            let detail = match (units > WINDOWS_PATH, bytes > LINUX_PATH) {
+                _ if dir_long => continue,
+                (_, true) => format!("{} bytes, over {} on Linux", bytes, LINUX_PATH),
+                (true, false) => format!("{} UTF-16 units, over {} on Windows", units, WINDOWS_PATH),
+                (false, false) => continue,
+            };
+            entries.push(PortabilityEntry { kind: PortabilityKind::LongPath, id: child.id(), name: child.name().to_owned(), detail });
+        }
+        // Names equal after normalization collide on macOS, names only differing in case collide on any case-insensitive volume
+        let normalized = collisions(&names, |name| name.nfc().collect());
+        let cased = collisions(&names, |name| name.nfc().collect::<String>().to_lowercase());
+        for (i, others) in normalized {
+            entries.push(PortabilityEntry {
+                kind: PortabilityKind::NormalizationCollision,
+                id: childs[i].id(),
+                name: names[i].to_owned(),
+                detail: format!("Same as {} after normalization", others.join(", "))
+            });
+        }
+        for (i, others) in cased {
+            let others: Vec<_> = others.into_iter().filter(|other| other.nfc().ne(names[i].nfc())).collect();
+            if !others.is_empty() {
+                entries.push(PortabilityEntry {
+                    kind: PortabilityKind::CaseCollision,
+                    id: childs[i].id(),
+                    name: names[i].to_owned(),
+                    detail: format!("Same as {} ignoring case", others.join(", "))
+                });
+            }
+        }
+        if !entries.is_empty() {
+            dirs.push(PortabilityDir { id: dir.id(), path: scan.path(dir.id()), entries });
+        }
+    }
+    PortabilityReport { dirs }
+}
@@ -0,0 +1,43 @@
+use crate::record::TableReport;
+use crate::scan::{FileInfo, Query, Scan, info_string, stringify_time};
+use serde::Serialize;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct QueryMatch {
+    pub id: usize,
+    pub path: String,
+    pub file_type: String,
+    pub size: Option<u64>,
+    pub modified: Option<i64>
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct QueryReport {
+    pub query: String,
+    pub matches: Vec<QueryMatch>
+}
+
+impl TableReport for QueryReport {
+    const TITLES: &'static [&'static str] = &["Path", "Type", "Size", "Modified"];
+
+    fn rows(&self) -> impl Iterator<Item = Vec<String>> + '_ {
+        self.matches.iter().map(|found| vec![
+            found.path.clone(),
+            found.file_type.clone(),
+            found.size.map(|size| size.to_string()).unwrap_or_default(),
+            stringify_time(found.modified),
+        ])
+    }
+}
+
+pub fn query(scan: &Scan, source: &str) -> Result<QueryReport, String> {
+    let query = Query::parse(source)?;
+    let matches = scan.items.iter().filter(|item| query.matches(item)).map(|item| QueryMatch {
+        id: item.id(),
+        path: scan.path(item.id()),
+        file_type: info_string(item, &FileInfo::Type),
+        size: item.size(),
+        modified: item.times().modified,
+    }).collect();
+    Ok(QueryReport { query: source.to_owned(), matches })
+}
@@ -0,0 +1,133 @@
+use crate::scan::{FileType, Scan, SpecificInfo};
+use chrono::Datelike;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+
+// Only this many of the largest files and directories are kept
+const TOP: usize = 20;
+
+const SIZE_BUCKETS: [(u64, &str); 8] = [
+    (1, "Empty"),
+    (1 << 10, "< 1 KiB"),
+    (1 << 14, "< 16 KiB"),
+    (1 << 18, "< 256 KiB"),
+    (1 << 22, "< 4 MiB"),
+    (1 << 26, "< 64 MiB"),
+    (1 << 30, "< 1 GiB"),
+    (1 << 34, "< 16 GiB"),
+];
+
+const CATEGORIES: [(&str, &[&str]); 6] = [
+    ("Image", &["jpg", "jpeg", "png", "gif", "bmp", "webp", "heic", "heif", "tif", "tiff", "svg", "ico", "raw", "cr2", "nef", "arw", "dng"]),
+    ("Video", &["mp4", "mkv", "avi", "mov", "wmv", "flv", "webm", "m4v", "mpg", "mpeg", "ts", "3gp"]),
+    ("Audio", &["mp3", "flac", "wav", "aac", "ogg", "m4a", "wma", "opus", "ape", "aiff"]),
+    ("Document", &["pdf", "doc", "docx", "xls", "xlsx", "ppt", "pptx", "odt", "ods", "odp", "txt", "md", "rtf", "epub", "csv"]),
+    ("Archive", &["zip", "rar", "7z", "tar", "gz", "xz", "zst", "bz2", "iso", "dmg"]),
+    ("Code", &["rs", "c", "h", "cpp", "hpp", "py", "js", "java", "go", "html", "css", "json", "toml", "yaml", "yml", "xml", "sh"]),
+];
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Tally {
+    pub name: String,
+    pub count: u64,
+    pub size: u64
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Ranked {
+    pub id: usize,
+    pub path: String,
+    pub size: u64
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Statistics {
+    // Regular files only, like every tally except the types
+    pub total: Tally,
+    pub types: Vec<Tally>,
+    pub categories: Vec<Tally>,
+    pub extensions: Vec<Tally>,
+    pub sizes: Vec<Tally>,
+    pub years: Vec<Tally>,
+    pub largest_files: Vec<Ranked>,
+    pub largest_dirs: Vec<Ranked>
+}
+
+impl Tally {
+    fn new(name: impl ToString) -> Self {
+        Self { name: name.to_string(), count: 0, size: 0 }
+    }
+
+    fn add(&mut self, size: u64) {
+        self.count += 1;
+        self.size += size;
+    }
+}
+
+fn extension(name: &str) -> String {
+    match name.rsplit_once('.') {
+        Some((stem, extension)) if !stem.is_empty() => extension.to_lowercase(),
+        _ => String::new(),
+    }
+}
+
+// The category of a file by its extension
+pub fn category(name: &str) -> &'static str {
+    let extension = extension(name);
+    CATEGORIES.iter().find(|(_, extensions)| extensions.contains(&extension.as_str())).map_or("Other", |(category, _)| category)
+}
+
+pub fn statistics(scan: &Scan) -> Statistics {
+    let mut total = Tally::new("Regular files");
+    let mut types: Vec<_> = ["Regular", "Directory", "Symlink", "Inaccessible"].map(Tally::new).into();
+    let mut categories: Vec<_> = CATEGORIES.iter().map(|(category, _)| Tally::new(category)).chain([Tally::new("Other")]).collect();
+    let mut extensions: HashMap<String, Tally> = HashMap::new();
+    let mut sizes: Vec<_> = SIZE_BUCKETS.iter().map(|(_, name)| Tally::new(name)).chain([Tally::new("16 GiB+")]).collect();
+    let mut years: BTreeMap<Option<i32>, Tally> = BTreeMap::new();
+    for item in &scan.items {
+        let size = item.size().unwrap_or_default();
+        let kind = match (item.info(), item.file_type()) {
+            (SpecificInfo::Inaccessible(_), _) => 3,
+            (_, FileType::Regular) => 0,
+            (_, FileType::Dir) => 1,
+            (_, FileType::Symlink) => 2,
+        };
+        types[kind].add(size);
+        if let SpecificInfo::Regular(_) = item.info() {
+            total.add(size);
+            let extension = extension(item.name());
+            let category = category(item.name());
+            categories.iter_mut().find(|tally| tally.name == category).unwrap().add(size);
+            let name = if extension.is_empty() { "(none)".to_owned() } else { format!(".{}", extension) };
+            extensions.entry(extension).or_insert_with(|| Tally::new(name)).add(size);
+            let bucket = SIZE_BUCKETS.iter().position(|(limit, _)| size < *limit).unwrap_or(SIZE_BUCKETS.len());
+            sizes[bucket].add(size);
+            let year = item.times().modified.map(|time| chrono::DateTime::from_timestamp_nanos(time).year());
+            years.entry(year).or_insert_with(|| Tally::new(year.map_or("Unknown".to_owned(), |year| year.to_string()))).add(size);
+        }
+    }
+    // Unknown years sort first, but read better last
+    let unknown = years.remove(&None);
+    let mut extensions: Vec<_> = extensions.into_values().collect();
+    extensions.sort_by(|tallya, tallyb| tallyb.size.cmp(&tallya.size).then_with(|| tallya.name.cmp(&tallyb.name)));
+    // Directories are ranked by their whole subtree
+    let subtrees = scan.subtrees();
+    let largest = |filter: fn(&FileType) -> bool| {
+        let mut ranked: Vec<_> = scan.items.iter()
+            .filter(|item| item.parent().is_some() && filter(&item.file_type()))
+            .map(|item| (subtrees[item.id()].size, item.id()))
+            .collect();
+        ranked.sort_by(|(sizea, ida), (sizeb, idb)| sizeb.cmp(sizea).then(ida.cmp(idb)));
+        ranked.into_iter().take(TOP).map(|(size, id)| Ranked { id, path: scan.path(id), size }).collect()
+    };
+    Statistics {
+        total,
+        types,
+        categories,
+        extensions,
+        sizes,
+        years: years.into_values().chain(unknown).collect(),
+        largest_files: largest(|file_type| *file_type == FileType::Regular),
+        largest_dirs: largest(|file_type| *file_type == FileType::Dir),
+    }
+}
@@ -0,0 +1,119 @@
+use crate::record::TableReport;
+use crate::scan::{stringify_time, FileType, Scan};
+use serde::Serialize;
+use std::collections::HashMap;
+
+// 1980/01/01, the earliest time of FAT and zip
+const EARLIEST: i64 = 315_532_800_000_000_000;
+// This many files modified at the same nanosecond are taken as touched together
+const BULK_TOUCH: usize = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+pub enum AnomalyKind {
+    Future,
+    CreatedAfterModified,
+    Early,
+    BulkTouch,
+    WholeSeconds,
+}
+
+impl AnomalyKind {
+    pub const ALL: [AnomalyKind; 5] = [
+        AnomalyKind::Future,
+        AnomalyKind::CreatedAfterModified,
+        AnomalyKind::Early,
+        AnomalyKind::BulkTouch,
+        AnomalyKind::WholeSeconds,
+    ];
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            AnomalyKind::Future => "In the future",
+            AnomalyKind::CreatedAfterModified => "Created after modified",
+            AnomalyKind::Early => "Before 1980",
+            AnomalyKind::BulkTouch => "Same modification time",
+            AnomalyKind::WholeSeconds => "Whole seconds",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AnomalyEntry {
+    pub kind: AnomalyKind,
+    pub id: usize,
+    pub path: String,
+    pub detail: String
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AnomalyReport {
+    // The scan time, or the time of the check for older records
+    pub reference: i64,
+    pub entries: Vec<AnomalyEntry>,
+    #[serde(skip)]
+    kinds: HashMap<usize, Vec<AnomalyKind>>
+}
+
+impl AnomalyReport {
+    pub fn count(&self, kind: AnomalyKind) -> usize {
+        self.entries.iter().filter(|entry| entry.kind == kind).count()
+    }
+
+    pub fn kinds(&self, id: usize) -> &[AnomalyKind] {
+        self.kinds.get(&id).map_or(&[], |kinds| kinds.as_slice())
+    }
+}
+
+impl TableReport for AnomalyReport {
+    const TITLES: &'static [&'static str] = &["Kind", "Path", "Detail"];
+
+    fn rows(&self) -> impl Iterator<Item = Vec<String>> + '_ {
+        self.entries.iter().map(|entry| vec![entry.kind.title().to_owned(), entry.path.clone(), entry.detail.clone()])
+    }
+}
+
+pub fn timestamps(scan: &Scan) -> AnomalyReport {
+    let reference = scan.time_or_now().unwrap_or(i64::MAX);
+    let mut touched: HashMap<i64, usize> = HashMap::new();
+    for item in &scan.items {
+        if let (FileType::Regular, Some(modified)) = (item.file_type(), item.times().modified) {
+            *touched.entry(modified).or_default() += 1;
+        }
+    }
+    let mut entries = Vec::new();
+    let mut push = |kind, id, detail: String| entries.push(AnomalyEntry { kind, id, path: scan.path(id), detail });
+    for item in &scan.items {
+        let (id, times) = (item.id(), item.times());
+        // The scan itself reads every file, which may update access times
+        let fields = [("Created", times.created), ("Modified", times.modified), ("Accessed", times.accessed)];
+        let known = || fields.iter().filter_map(|(field, time)| time.map(|time| (*field, time)));
+        let future: Vec<_> = known().filter(|(field, time)| *field != "Accessed" && *time > reference).collect();
+        if !future.is_empty() {
+            let detail = future.iter().map(|(field, time)| format!("{} {}", field, stringify_time(Some(*time)))).collect::<Vec<_>>().join(", ");
+            push(AnomalyKind::Future, id, format!("{}, after the scan at {}", detail, stringify_time(Some(reference))));
+        }
+        if let (Some(created), Some(modified)) = (times.created, times.modified) && created > modified {
+            push(AnomalyKind::CreatedAfterModified, id, format!("Created {}, modified {}", stringify_time(Some(created)), stringify_time(Some(modified))));
+        }
+        let early: Vec<_> = known().filter(|(_, time)| *time < EARLIEST).collect();
+        if !early.is_empty() {
+            let detail = early.iter().map(|(field, time)| format!("{} {}", field, stringify_time(Some(*time)))).collect::<Vec<_>>().join(", ");
+            push(AnomalyKind::Early, id, detail);
+        }
+        if let (FileType::Regular, Some(modified)) = (item.file_type(), times.modified) {
+            let count = touched[&modified];
+            if count >= BULK_TOUCH {
+                push(AnomalyKind::BulkTouch, id, format!("{} files modified at {}", count, stringify_time(Some(modified))));
+            }
+        }
+        // Copies through FAT and some archives drop the fraction of a second, the copy is created anew
+        if let Some(modified) = times.modified.filter(|modified| modified % 1_000_000_000 == 0) {
+            push(AnomalyKind::WholeSeconds, id, format!("Modified {}", stringify_time(Some(modified))));
+        }
+    }
+    let mut kinds: HashMap<usize, Vec<AnomalyKind>> = HashMap::new();
+    for entry in &entries {
+        kinds.entry(entry.id).or_default().push(entry.kind);
+    }
+    AnomalyReport { reference, entries, kinds }
+}
@@ -0,0 +1,55 @@
+use super::statistics::category;
+use crate::scan::{Scan, SpecificInfo, item_path};
+use std::ops::RangeInclusive;
+
+#[derive(Debug, Clone)]
+pub struct UsageNode {
+    pub name: String,
+    pub parent: Option<usize>,
+    pub childs: Option<RangeInclusive<usize>>,
+    // Bytes and items inside, both with the node itself
+    pub size: u64,
+    pub count: u64,
+    pub category: &'static str,
+    pub modified: Option<i64>
+}
+
+// Enough of a scan to draw it, kept apart as a catalog scan is never in memory as a whole
+#[derive(Debug, Clone)]
+pub struct DiskUsage {
+    pub nodes: Vec<UsageNode>,
+    // The scan time, or the time of the computation for older records
+    pub reference: i64
+}
+
+impl DiskUsage {
+    pub fn path(&self, id: usize) -> String {
+        item_path(id, |id| (self.nodes[id].name.as_str(), self.nodes[id].parent))
+    }
+
+    // The node and its ancestors, the root first
+    pub fn ancestors(&self, id: usize) -> Vec<usize> {
+        let mut ids = vec![id];
+        while let Some(parent) = self.nodes[*ids.last().unwrap()].parent {
+            ids.push(parent);
+        }
+        ids.reverse();
+        ids
+    }
+}
+
+pub fn disk_usage(scan: &Scan) -> DiskUsage {
+    let nodes = scan.items.iter().zip(scan.subtrees()).map(|(item, subtree)| UsageNode {
+        name: item.name().to_owned(),
+        parent: item.parent(),
+        childs: item.childs(),
+        size: subtree.size,
+        count: subtree.count,
+        category: match item.info() {
+            SpecificInfo::Regular(_) => category(item.name()),
+            _ => "Other",
+        },
+        modified: item.times().modified
+    }).collect();
+    DiskUsage { nodes, reference: scan.time_or_now().unwrap_or_default() }
+}
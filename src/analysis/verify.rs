@@ -0,0 +1,111 @@
+use super::file_md5;
+use crate::scan::{FileTimes, FileType, Scan, SpecificInfo};
+use serde::Serialize;
+use std::{collections::HashSet, path::Path};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+pub enum VerifyStatus {
+    Ok,
+    // Content changed but the modification time did not, which a normal write would have updated
+    BitRot,
+    Modified,
+    // Content changed and the modification time went back, like an older copy put in its place
+    Older,
+    Missing,
+    New,
+    TypeChanged,
+    Unreadable,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct VerifyEntry {
+    pub id: Option<usize>,
+    pub path: String,
+    pub status: VerifyStatus
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct VerifyReport {
+    pub root: String,
+    pub ok: usize,
+    // Entries that are not ok
+    pub entries: Vec<VerifyEntry>
+}
+
+impl VerifyReport {
+    pub fn count(&self, status: VerifyStatus) -> usize {
+        match status {
+            VerifyStatus::Ok => self.ok,
+            status => self.entries.iter().filter(|entry| entry.status == status).count(),
+        }
+    }
+}
+
+pub fn verify(scan: &Scan, root: &Path) -> VerifyReport {
+    let mut ok = 0;
+    let mut entries = Vec::new();
+    // Items under a missing or replaced directory are not reported one by one
+    let mut gone = vec![false; scan.items.len()];
+    for item in &scan.items {
+        if item.parent().is_some_and(|parent| gone[parent]) {
+            gone[item.id()] = true;
+            continue;
+        }
+        let relative = scan.path(item.id());
+        let path = root.join(&relative);
+        let status = match std::fs::symlink_metadata(&path) {
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => VerifyStatus::Missing,
+            Err(_) => VerifyStatus::Unreadable,
+            Ok(meta) => {
+                let file_type = match meta.file_type() {
+                    file_type if file_type.is_symlink() => FileType::Symlink,
+                    file_type if file_type.is_dir() => FileType::Dir,
+                    _ => FileType::Regular,
+                };
+                match (item.info(), file_type == item.file_type()) {
+                    (_, false) => VerifyStatus::TypeChanged,
+                    (SpecificInfo::Regular(regular), true) => match file_md5(&path).ok() {
+                        None => VerifyStatus::Unreadable,
+                        Some(md5) if md5 == regular.md5 => VerifyStatus::Ok,
+                        Some(_) => match (FileTimes::from(meta).modified, item.times().modified) {
+                            (Some(now), Some(then)) if now == then => VerifyStatus::BitRot,
+                            (Some(now), Some(then)) if now < then => VerifyStatus::Older,
+                            _ => VerifyStatus::Modified,
+                        },
+                    },
+                    // Entries left out by the scan limit would all look new
+                    (SpecificInfo::Dir(dir), true) => {
+                        if !dir.truncated {
+                            new_entries(scan, item.id(), &path, &relative, &mut entries);
+                        }
+                        VerifyStatus::Ok
+                    }
+                    _ => VerifyStatus::Ok,
+                }
+            }
+        };
+        gone[item.id()] = matches!(status, VerifyStatus::Missing | VerifyStatus::TypeChanged);
+        match status {
+            VerifyStatus::Ok => ok += 1,
+            status => entries.push(VerifyEntry { id: Some(item.id()), path: relative, status }),
+        }
+    }
+    VerifyReport { root: root.to_string_lossy().into_owned(), ok, entries }
+}
+
+fn new_entries(scan: &Scan, id: usize, path: &Path, relative: &str, entries: &mut Vec<VerifyEntry>) {
+    let Ok(read) = std::fs::read_dir(path) else {
+        return;
+    };
+    let recorded: HashSet<_> = scan.items[scan.items[id].childs().unwrap()].iter().map(|item| item.name()).collect();
+    for entry in read.flatten() {
+        let name = entry.file_name().to_string_lossy().into_owned();
+        if !recorded.contains(name.as_str()) {
+            let path = match relative {
+                "." => name,
+                relative => format!("{}/{}", relative, name),
+            };
+            entries.push(VerifyEntry { id: None, path, status: VerifyStatus::New });
+        }
+    }
+}
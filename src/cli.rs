@@ -0,0 +1,56 @@
+use crate::{analysis, record};
+use std::path::Path;
+
+const USAGE: &str = "Usage: sixx-fs verify <record> [--root <dir>] [--report <file>]";
+
+pub fn run(args: &[String]) -> i32 {
+    match args.first().map(String::as_str) {
+        Some("verify") => verify(&args[1..]),
+        _ => {
+            eprintln!("{}", USAGE);
+            2
+        }
+    }
+}
+
+// Exits with 0 if everything is ok, 1 if there are problems and 2 on errors
+fn verify(args: &[String]) -> i32 {
+    let (mut record_path, mut root, mut report_path) = (None, None, None);
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--root" => root = args.next(),
+            "--report" => report_path = args.next(),
+            _ => record_path = Some(arg),
+        }
+    }
+    let Some(record_path) = record_path else {
+        eprintln!("{}", USAGE);
+        return 2;
+    };
+    let scan = match record::load(Path::new(record_path)) {
+        Err(err) => {
+            eprintln!("{}", err);
+            return 2;
+        }
+        Ok(scan) => scan,
+    };
+    let root = root.unwrap_or(&scan.root);
+    if root.is_empty() {
+        eprintln!("The record has no root, specify one with `--root`");
+        return 2;
+    }
+    let report = analysis::verify(&scan, Path::new(root));
+    let result = match report_path {
+        Some(path) => record::export_json(&report, Path::new(path)),
+        None => serde_json::to_string_pretty(&report).map(|json| println!("{}", json)).map_err(|_| record::RecordError::InvalidContent),
+    };
+    if let Err(err) = result {
+        eprintln!("{}", err);
+        return 2;
+    }
+    match report.entries.is_empty() {
+        true => 0,
+        false => 1,
+    }
+}
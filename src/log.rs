@@ -1,4 +1,5 @@
-pub fn setup() {
+// Headless output goes to stdout, so logs go to stderr then
+pub fn setup(headless: bool) {
     let colors = fern::colors::ColoredLevelConfig::new().info(fern::colors::Color::BrightCyan);
     let _ = fern::Dispatch::new()
         .format(move |out, message, record| {
@@ -12,6 +13,9 @@ pub fn setup() {
         })
         .level(log::LevelFilter::Error)
         .level_for(module_path!().split("::").next().unwrap(), log::LevelFilter::Trace)
-        .chain(std::io::stdout())
+        .chain(match headless {
+            true => fern::Output::from(std::io::stderr()),
+            false => fern::Output::from(std::io::stdout()),
+        })
         .apply();
 }
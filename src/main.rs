@@ -1,10 +1,17 @@
 #![allow(dead_code)]
 
+mod analysis;
+mod cli;
 mod log;
+mod record;
 mod scan;
 mod ui;
 
 fn main() -> iced::Result {
-    log::setup();
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    log::setup(!args.is_empty());
+    if !args.is_empty() {
+        std::process::exit(cli::run(&args));
+    }
     ui::setup()
 }
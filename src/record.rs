@@ -0,0 +1,110 @@
+mod binary;
+mod catalog;
+mod checksum;
+mod compress;
+mod json;
+mod migrate;
+mod report;
+mod table;
+pub use catalog::{Catalog, CatalogScan, is_catalog};
+pub use checksum::{export_checksums, import_checksums};
+pub use compress::Compression;
+pub use report::{TableReport, export_json, export_report};
+pub use table::export_table;
+
+use crate::scan::Scan;
+use serde::Deserialize;
+use std::{
+    fmt::Display,
+    fs::File,
+    io::{BufReader, BufWriter, Write},
+    path::Path,
+};
+
+pub const VERSION: u32 = 9;
+
+#[derive(Debug, Clone)]
+pub enum RecordError {
+    Io(String),
+    InvalidContent,
+    NewerVersion(u32),
+    Catalog(String),
+    Manifest(String),
+}
+
+impl Display for RecordError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RecordError::Io(err) => write!(f, "File IO error: {}", err),
+            RecordError::InvalidContent => write!(f, "Invalid record content"),
+            RecordError::NewerVersion(version) => write!(f, "Record version {} is newer than the supported version {}", version, VERSION),
+            RecordError::Catalog(err) => write!(f, "Catalog error: {}", err),
+            RecordError::Manifest(err) => write!(f, "Manifest error: {}", err),
+        }
+    }
+}
+
+impl From<std::io::Error> for RecordError {
+    fn from(value: std::io::Error) -> Self {
+        RecordError::Io(value.to_string())
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum RecordFormat {
+    Json,
+    Binary,
+    Catalog,
+}
+
+impl RecordFormat {
+    pub fn from_path(path: &Path) -> Self {
+        // The format of `scan.json.zst` is decided by `scan.json`
+        let path = match Compression::from_extension(path) {
+            Compression::Plain => path,
+            _ => Path::new(path.file_stem().unwrap_or_default()),
+        };
+        match path.extension().and_then(|ext| ext.to_str()).map(str::to_ascii_lowercase).as_deref() {
+            Some("json" | "ndjson" | "jsonl") => RecordFormat::Json,
+            Some("db" | "sqlite" | "sqlite3") => RecordFormat::Catalog,
+            _ => RecordFormat::Binary,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct Record {
+    version: u32,
+    scan: Scan
+}
+
+pub fn save(scan: &Scan, path: &Path, compression: Compression) -> Result<(), RecordError> {
+    let format = RecordFormat::from_path(path);
+    if format == RecordFormat::Catalog {
+        return Catalog::open_to_insert(path)?.insert(scan).map(|_| ());
+    }
+    let writer = BufWriter::new(File::create(path)?);
+    let mut writer = compress::compressed_write(writer, compression.resolve(path), |writer| match format {
+        RecordFormat::Json => json::write(scan, writer),
+        _ => binary::write(scan, writer),
+    })?;
+    writer.flush()?;
+    Ok(())
+}
+
+pub fn load(path: &Path) -> Result<Scan, RecordError> {
+    if path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("md5")) {
+        return import_checksums(path);
+    }
+    // A catalog loads as its latest scan
+    if is_catalog(path) {
+        let catalog = Catalog::open(path)?;
+        let scan = catalog.scans()?.pop().ok_or(RecordError::InvalidContent)?;
+        return catalog.load(scan.id);
+    }
+    let reader = BufReader::new(File::open(path)?);
+    compress::decompressed_read(reader, |reader| match binary::is_binary(reader.fill_buf()?) {
+        true => binary::read(reader),
+        false => json::read(reader),
+    })
+}
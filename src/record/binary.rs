@@ -0,0 +1,35 @@
+use super::{Record, RecordError, VERSION, migrate};
+use crate::scan::Scan;
+use serde_json::json;
+use std::io::{Read, Write};
+
+// A binary record is the magic, the version as little endian u32 and then the `Scan` in MessagePack
+const MAGIC: &[u8; 8] = b"SIXXSCAN";
+
+pub fn is_binary(head: &[u8]) -> bool {
+    head.starts_with(MAGIC)
+}
+
+pub fn write(scan: &Scan, mut writer: impl Write) -> Result<(), RecordError> {
+    writer.write_all(MAGIC)?;
+    writer.write_all(&VERSION.to_le_bytes())?;
+    // Field names are kept so that migrations can work on the same shape as json records
+    rmp_serde::encode::write_named(&mut writer, scan).map_err(|err| RecordError::Io(err.to_string()))?;
+    writer.flush()?;
+    Ok(())
+}
+
+pub fn read(mut reader: impl Read) -> Result<Scan, RecordError> {
+    let mut header = [0; MAGIC.len() + 4];
+    reader.read_exact(&mut header).map_err(|_| RecordError::InvalidContent)?;
+    let version = u32::from_le_bytes(header[MAGIC.len()..].try_into().unwrap());
+    match version {
+        VERSION => rmp_serde::from_read(reader).map_err(|_| RecordError::InvalidContent),
+        version if version > VERSION => Err(RecordError::NewerVersion(version)),
+        version => {
+            let scan: serde_json::Value = rmp_serde::from_read(reader).map_err(|_| RecordError::InvalidContent)?;
+            let value = migrate::migrate(json!({ "version": version, "scan": scan }), version)?;
+            serde_json::from_value::<Record>(value).map(|record| record.scan).map_err(|_| RecordError::InvalidContent)
+        }
+    }
+}
@@ -0,0 +1,491 @@
+use super::{RecordError, VERSION};
+use crate::scan::{DirInfo, FileItem, FileMode, FileTimes, FileType, InaccessibleInfo, MediaInfo, RegularInfo, Scan, SpecificInfo, SymlinkInfo};
+use rusqlite::{Connection, OpenFlags, OptionalExtension, Row, params};
+use std::{fmt::Display, path::Path};
+
+const MAGIC: &[u8; 16] = b"SQLite format 3\0";
+const SCHEMA_VERSION: u32 = 7;
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS scans (
+        id INTEGER PRIMARY KEY,
+        version INTEGER NOT NULL,
+        created INTEGER NOT NULL,
+        root TEXT NOT NULL,
+        description TEXT NOT NULL,
+        warning TEXT NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS items (
+        scan INTEGER NOT NULL REFERENCES scans (id) ON DELETE CASCADE,
+        id INTEGER NOT NULL,
+        name TEXT NOT NULL,
+        parent INTEGER,
+        type TEXT NOT NULL,
+        accessible INTEGER NOT NULL,
+        childs_start INTEGER,
+        childs_end INTEGER,
+        target INTEGER,
+        created INTEGER,
+        modified INTEGER,
+        accessed INTEGER,
+        size INTEGER,
+        device INTEGER,
+        inode INTEGER,
+        reason TEXT,
+        truncated INTEGER,
+        link TEXT,
+        broken INTEGER,
+        probed INTEGER,
+        duration INTEGER,
+        video_codec TEXT,
+        width INTEGER,
+        height INTEGER,
+        audio_codec TEXT,
+        probe_error TEXT,
+        mode INTEGER,
+        uid INTEGER,
+        gid INTEGER,
+        orphan INTEGER,
+        PRIMARY KEY (scan, id)
+    ) WITHOUT ROWID;
+    CREATE TABLE IF NOT EXISTS hashes (
+        scan INTEGER NOT NULL REFERENCES scans (id) ON DELETE CASCADE,
+        item INTEGER NOT NULL,
+        algorithm TEXT NOT NULL,
+        digest TEXT NOT NULL,
+        PRIMARY KEY (scan, item, algorithm)
+    ) WITHOUT ROWID;
+    CREATE INDEX IF NOT EXISTS hashes_digest ON hashes (algorithm, digest);
+    CREATE TABLE IF NOT EXISTS metas (
+        scan INTEGER NOT NULL REFERENCES scans (id) ON DELETE CASCADE,
+        item INTEGER NOT NULL,
+        key TEXT NOT NULL,
+        value TEXT NOT NULL
+    );
+    CREATE INDEX IF NOT EXISTS metas_item ON metas (scan, item);
+";
+// The migration at index `n` upgrades a catalog schema of version `n + 1` to version `n + 2`
+const SCHEMA_MIGRATIONS: [&str; SCHEMA_VERSION as usize - 1] = [
+    "ALTER TABLE scans ADD COLUMN root TEXT NOT NULL DEFAULT '';",
+    "ALTER TABLE items ADD COLUMN size INTEGER;",
+    "ALTER TABLE items ADD COLUMN device INTEGER; ALTER TABLE items ADD COLUMN inode INTEGER;",
+    "ALTER TABLE items ADD COLUMN reason TEXT; ALTER TABLE items ADD COLUMN truncated INTEGER;
+     ALTER TABLE items ADD COLUMN link TEXT; ALTER TABLE items ADD COLUMN broken INTEGER;",
+    "ALTER TABLE items ADD COLUMN probed INTEGER; ALTER TABLE items ADD COLUMN duration INTEGER;
+     ALTER TABLE items ADD COLUMN video_codec TEXT; ALTER TABLE items ADD COLUMN width INTEGER; ALTER TABLE items ADD COLUMN height INTEGER;
+     ALTER TABLE items ADD COLUMN audio_codec TEXT; ALTER TABLE items ADD COLUMN probe_error TEXT;",
+    "ALTER TABLE items ADD COLUMN mode INTEGER; ALTER TABLE items ADD COLUMN uid INTEGER;
+     ALTER TABLE items ADD COLUMN gid INTEGER; ALTER TABLE items ADD COLUMN orphan INTEGER;",
+];
+const ITEM_COLUMNS: &str = "items.id, name, parent, type, accessible, childs_start, childs_end, target, created, modified, accessed, hashes.digest, items.size, items.device, items.inode, items.reason, items.truncated, items.link, items.broken, \
+    items.probed, items.duration, items.video_codec, items.width, items.height, items.audio_codec, items.probe_error, \
+    items.mode, items.uid, items.gid, items.orphan, merkle.digest";
+// Regular files have an md5 and directories a Merkle hash
+const ITEM_JOIN: &str = "LEFT JOIN hashes ON hashes.scan = items.scan AND hashes.item = items.id AND hashes.algorithm = 'md5' \
+    LEFT JOIN hashes AS merkle ON merkle.scan = items.scan AND merkle.item = items.id AND merkle.algorithm = 'merkle'";
+
+impl From<rusqlite::Error> for RecordError {
+    fn from(value: rusqlite::Error) -> Self {
+        RecordError::Catalog(value.to_string())
+    }
+}
+
+pub struct Catalog {
+    conn: Connection,
+    // Older catalogs are read as they are, without the columns added since
+    item_columns: String,
+    scan_root: &'static str
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CatalogScan {
+    pub id: i64,
+    pub created: i64,
+    pub root: String,
+    pub description: String,
+    pub warning: String
+}
+
+impl Display for CatalogScan {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let created = chrono::DateTime::from_timestamp_nanos(self.created).with_timezone(&chrono::Local);
+        write!(f, "#{} {} {}", self.id, created.format("%Y/%m/%d %H:%M:%S"), self.description)
+    }
+}
+
+// Zero for a new catalog, anything else needs the tables of one
+fn schema_version(conn: &Connection) -> Result<u32, RecordError> {
+    let schema: u32 = conn.pragma_query_value(None, "user_version", |row| row.get(0))?;
+    let tables: u32 = conn.query_row("SELECT count(*) FROM sqlite_master WHERE type = 'table' AND name IN ('scans', 'items')", [], |row| row.get(0))?;
+    match (schema, tables) {
+        (0, _) => Ok(0),
+        (_, 2) if schema > SCHEMA_VERSION => Err(RecordError::NewerVersion(schema)),
+        (_, 2) => Ok(schema),
+        _ => Err(RecordError::InvalidContent),
+    }
+}
+
+pub fn is_catalog(path: &Path) -> bool {
+    let mut head = [0; MAGIC.len()];
+    std::fs::File::open(path).and_then(|mut file| std::io::Read::read_exact(&mut file, &mut head)).is_ok() && &head == MAGIC
+}
+
+impl Catalog {
+    // Loading and browsing never write, so other SQLite files and older catalogs are left as they are
+    pub fn open(path: &Path) -> Result<Self, RecordError> {
+        let conn = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX)?;
+        let schema = schema_version(&conn)?;
+        if schema == 0 {
+            return Err(RecordError::InvalidContent);
+        }
+        Self::new(conn)
+    }
+
+    // Creates the catalog or upgrades an older one, all or nothing
+    pub fn open_to_insert(path: &Path) -> Result<Self, RecordError> {
+        let mut conn = Connection::open(path)?;
+        let schema = schema_version(&conn)?;
+        // A new catalog has no tables yet, any other file without a schema is not a catalog
+        let tables: u32 = conn.query_row("SELECT count(*) FROM sqlite_master WHERE type = 'table'", [], |row| row.get(0))?;
+        if schema == 0 && tables > 0 {
+            return Err(RecordError::InvalidContent);
+        }
+        let tx = conn.transaction()?;
+        if schema > 0 {
+            for migration in &SCHEMA_MIGRATIONS[schema as usize - 1..] {
+                tx.execute_batch(migration)?;
+            }
+        }
+        tx.execute_batch(SCHEMA)?;
+        tx.pragma_update(None, "user_version", SCHEMA_VERSION)?;
+        tx.commit()?;
+        conn.pragma_update(None, "foreign_keys", true)?;
+        Self::new(conn)
+    }
+
+    fn new(conn: Connection) -> Result<Self, RecordError> {
+        let mut stmt = conn.prepare("SELECT name FROM pragma_table_info('items')")?;
+        let existing = stmt.query_map([], |row| row.get::<_, String>(0))?.collect::<Result<Vec<_>, _>>()?;
+        drop(stmt);
+        let item_columns = ITEM_COLUMNS.split(',').map(str::trim).map(|column| {
+            let name = column.strip_prefix("items.").unwrap_or(column);
+            // Hashes are joined from their own table
+            match name.contains('.') || existing.iter().any(|existing| existing == name) {
+                true => column,
+                false => "NULL",
+            }
+        }).collect::<Vec<_>>().join(", ");
+        let root: u32 = conn.query_row("SELECT count(*) FROM pragma_table_info('scans') WHERE name = 'root'", [], |row| row.get(0))?;
+        let scan_root = if root > 0 { "root" } else { "''" };
+        Ok(Self { conn, item_columns, scan_root })
+    }
+
+    pub fn insert(&mut self, scan: &Scan) -> Result<i64, RecordError> {
+        let tx = self.conn.transaction()?;
+        // Older records are taken as scanned when inserted
+        let created = scan.time_or_now().unwrap_or_default();
+        tx.execute(
+            "INSERT INTO scans (version, created, root, description, warning) VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![VERSION, created, scan.root, scan.description, scan.warning],
+        )?;
+        let scan_id = tx.last_insert_rowid();
+        {
+            let mut insert_item = tx.prepare("INSERT INTO items VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21, ?22, ?23, ?24, ?25, ?26, ?27, ?28, ?29, ?30)")?;
+            let mut insert_hash = tx.prepare("INSERT INTO hashes VALUES (?1, ?2, ?3, ?4)")?;
+            let mut insert_meta = tx.prepare("INSERT INTO metas VALUES (?1, ?2, ?3, ?4)")?;
+            for item in &scan.items {
+                let (mut childs, mut target, mut size, mut inode) = (None, None, None, None);
+                let (mut reason, mut truncated, mut link, mut broken) = (None, None, None, None);
+                let (mut media, mut probe_error) = (None, None);
+                match item.info() {
+                    SpecificInfo::Dir(dir) => {
+                        childs = Some(dir.childs.clone());
+                        truncated = Some(dir.truncated);
+                        if let Some(hash) = &dir.hash {
+                            insert_hash.execute(params![scan_id, item.id(), "merkle", hash])?;
+                        }
+                    }
+                    SpecificInfo::Symlink(symlink) => {
+                        target = symlink.target;
+                        link = symlink.link.as_deref();
+                        broken = symlink.broken;
+                    }
+                    SpecificInfo::Regular(regular) => {
+                        size = regular.size;
+                        inode = regular.inode;
+                        media = regular.media.as_ref();
+                        probe_error = regular.probe_error.as_deref();
+                        insert_hash.execute(params![scan_id, item.id(), "md5", regular.md5])?;
+                        for (key, value) in &regular.metas {
+                            insert_meta.execute(params![scan_id, item.id(), key, value])?;
+                        }
+                    }
+                    SpecificInfo::Inaccessible(inaccessible) => reason = inaccessible.reason.as_deref()
+                }
+                let times = item.times();
+                insert_item.execute(params![
+                    scan_id,
+                    item.id(),
+                    item.name(),
+                    item.parent(),
+                    type_name(&item.file_type()),
+                    !matches!(item.info(), SpecificInfo::Inaccessible(_)),
+                    childs.as_ref().map(|childs| *childs.start()),
+                    childs.as_ref().map(|childs| *childs.end()),
+                    target,
+                    times.created,
+                    times.modified,
+                    times.accessed,
+                    size,
+                    // SQLite integers are signed
+                    inode.map(|(device, _)| device as i64),
+                    inode.map(|(_, inode)| inode as i64),
+                    reason,
+                    truncated,
+                    link,
+                    broken,
+                    media.is_some(),
+                    media.and_then(|media| media.duration),
+                    media.and_then(|media| media.video_codec.as_deref()),
+                    media.and_then(|media| media.width),
+                    media.and_then(|media| media.height),
+                    media.and_then(|media| media.audio_codec.as_deref()),
+                    probe_error,
+                    item.mode().map(|mode| mode.mode),
+                    item.mode().map(|mode| mode.uid),
+                    item.mode().map(|mode| mode.gid),
+                    item.mode().map(|mode| mode.orphan)
+                ])?;
+            }
+        }
+        tx.commit()?;
+        Ok(scan_id)
+    }
+
+    pub fn scans(&self) -> Result<Vec<CatalogScan>, RecordError> {
+        let mut stmt = self.conn.prepare(&format!("SELECT id, created, {}, description, warning FROM scans ORDER BY id", self.scan_root))?;
+        let scans = stmt.query_map([], |row| Ok(CatalogScan {
+            id: row.get(0)?,
+            created: row.get(1)?,
+            root: row.get(2)?,
+            description: row.get(3)?,
+            warning: row.get(4)?
+        }))?;
+        Ok(scans.collect::<Result<_, _>>()?)
+    }
+
+    pub fn item(&self, scan: i64, id: usize) -> Result<FileItem, RecordError> {
+        let sql = format!("SELECT {} FROM items {} WHERE items.scan = ?1 AND items.id = ?2", self.item_columns, ITEM_JOIN);
+        let item = self.conn.prepare_cached(&sql)?.query_row(params![scan, id], |row| self.item_from_row(scan, row)).optional()?;
+        item.ok_or(RecordError::InvalidContent)
+    }
+
+    pub fn childs(&self, scan: i64, item: &FileItem) -> Result<Vec<FileItem>, RecordError> {
+        let Some(childs) = item.childs() else {
+            return Ok(Vec::new());
+        };
+        let sql = format!("SELECT {} FROM items {} WHERE items.scan = ?1 AND items.id BETWEEN ?2 AND ?3 ORDER BY items.id", self.item_columns, ITEM_JOIN);
+        let mut stmt = self.conn.prepare_cached(&sql)?;
+        let items = stmt.query_map(params![scan, childs.start(), childs.end()], |row| self.item_from_row(scan, row))?;
+        Ok(items.collect::<Result<_, _>>()?)
+    }
+
+    // Accessible directories inside a directory by name, with whether they have anything inside
+    pub fn child_dirs(&self, scan: i64, id: usize) -> Result<Vec<(usize, String, bool)>, RecordError> {
+        let mut stmt = self.conn.prepare_cached("SELECT childs.id, childs.name, childs.childs_start <= childs.childs_end FROM items JOIN items AS childs \
+            ON childs.scan = items.scan AND childs.id BETWEEN items.childs_start AND items.childs_end \
+            WHERE items.scan = ?1 AND items.id = ?2 AND childs.type = 'Dir' AND childs.accessible ORDER BY childs.name")?;
+        let dirs = stmt.query_map(params![scan, id], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?;
+        Ok(dirs.collect::<Result<_, _>>()?)
+    }
+
+    // Names and parents by id, enough to search names and build paths
+    pub fn names(&self, scan: i64) -> Result<Vec<(String, Option<usize>)>, RecordError> {
+        let mut stmt = self.conn.prepare("SELECT name, parent FROM items WHERE scan = ?1 ORDER BY id")?;
+        let names = stmt.query_map([scan], |row| Ok((row.get(0)?, row.get(1)?)))?;
+        Ok(names.collect::<Result<_, _>>()?)
+    }
+
+    // Directories by id with their Merkle hashes
+    pub fn dir_hashes(&self, scan: i64) -> Result<Vec<(usize, String)>, RecordError> {
+        let mut stmt = self.conn.prepare("SELECT item, digest FROM hashes WHERE scan = ?1 AND algorithm = 'merkle'")?;
+        let hashes = stmt.query_map([scan], |row| Ok((row.get(0)?, row.get(1)?)))?;
+        Ok(hashes.collect::<Result<_, _>>()?)
+    }
+
+    pub fn load(&self, scan: i64) -> Result<Scan, RecordError> {
+        let (root, description, warning, time) = self.conn.query_row(&format!("SELECT {}, description, warning, created FROM scans WHERE id = ?1", self.scan_root), [scan], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)))?;
+        let sql = format!("SELECT {} FROM items {} WHERE items.scan = ?1 ORDER BY items.id", self.item_columns, ITEM_JOIN);
+        let mut stmt = self.conn.prepare(&sql)?;
+        let items = stmt.query_map([scan], |row| self.item_from_row(scan, row))?;
+        let items = items.collect::<Result<_, _>>()?;
+        Ok(Scan { items, root, description, warning, time: Some(time) })
+    }
+
+    fn item_from_row(&self, scan: i64, row: &Row) -> rusqlite::Result<FileItem> {
+        let id: usize = row.get(0)?;
+        let file_type = match row.get::<_, String>(3)?.as_str() {
+            "Dir" => FileType::Dir,
+            "Symlink" => FileType::Symlink,
+            _ => FileType::Regular,
+        };
+        let info = match (row.get::<_, bool>(4)?, file_type) {
+            (false, file_type) => SpecificInfo::Inaccessible(InaccessibleInfo { file_type, reason: row.get(15)? }),
+            (true, FileType::Dir) => SpecificInfo::Dir(DirInfo {
+                hash: row.get(30)?,
+                ..DirInfo::new(row.get(5)?, row.get(6)?, row.get::<_, Option<bool>>(16)?.unwrap_or_default())
+            }),
+            (true, FileType::Symlink) => SpecificInfo::Symlink(SymlinkInfo { target: row.get(7)?, link: row.get(17)?, broken: row.get(18)? }),
+            (true, FileType::Regular) => {
+                let mut stmt = self.conn.prepare_cached("SELECT key, value FROM metas WHERE scan = ?1 AND item = ?2 ORDER BY rowid")?;
+                let metas = stmt.query_map(params![scan, id], |row| Ok((row.get(0)?, row.get(1)?)))?;
+                SpecificInfo::Regular(RegularInfo {
+                    md5: row.get::<_, Option<String>>(11)?.unwrap_or_default(),
+                    size: row.get(12)?,
+                    inode: row.get::<_, Option<i64>>(13)?.zip(row.get::<_, Option<i64>>(14)?).map(|(device, inode)| (device as u64, inode as u64)),
+                    metas: metas.collect::<Result<_, _>>()?,
+                    media: match row.get::<_, Option<bool>>(19)?.unwrap_or_default() {
+                        false => None,
+                        true => Some(MediaInfo {
+                            duration: row.get(20)?,
+                            video_codec: row.get(21)?,
+                            width: row.get(22)?,
+                            height: row.get(23)?,
+                            audio_codec: row.get(24)?
+                        }),
+                    },
+                    probe_error: row.get(25)?
+                })
+            }
+        };
+        let times = FileTimes {
+            created: row.get(8)?,
+            modified: row.get(9)?,
+            accessed: row.get(10)?
+        };
+        let mode = match row.get::<_, Option<u32>>(26)? {
+            None => None,
+            Some(mode) => Some(FileMode { mode, uid: row.get(27)?, gid: row.get(28)?, orphan: row.get(29)? }),
+        };
+        Ok(FileItem::new(id, row.get(1)?, row.get(2)?, info, times, mode))
+    }
+}
+
+fn type_name(file_type: &FileType) -> &'static str {
+    match file_type {
+        FileType::Regular => "Regular",
+        FileType::Dir => "Dir",
+        FileType::Symlink => "Symlink",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    // The first schema, before the root, sizes, inodes and the rest were recorded
+    const FIRST_SCHEMA: &str = "
+        CREATE TABLE scans (id INTEGER PRIMARY KEY, version INTEGER NOT NULL, created INTEGER NOT NULL, description TEXT NOT NULL, warning TEXT NOT NULL);
+        CREATE TABLE items (
+            scan INTEGER NOT NULL REFERENCES scans (id) ON DELETE CASCADE, id INTEGER NOT NULL, name TEXT NOT NULL, parent INTEGER, type TEXT NOT NULL,
+            accessible INTEGER NOT NULL, childs_start INTEGER, childs_end INTEGER, target INTEGER, created INTEGER, modified INTEGER, accessed INTEGER,
+            PRIMARY KEY (scan, id)
+        ) WITHOUT ROWID;
+        CREATE TABLE hashes (scan INTEGER NOT NULL, item INTEGER NOT NULL, algorithm TEXT NOT NULL, digest TEXT NOT NULL, PRIMARY KEY (scan, item, algorithm)) WITHOUT ROWID;
+        CREATE TABLE metas (scan INTEGER NOT NULL, item INTEGER NOT NULL, key TEXT NOT NULL, value TEXT NOT NULL);
+        INSERT INTO scans VALUES (1, 1, 5, 'first', '');
+        INSERT INTO items VALUES (1, 0, '', NULL, 'Dir', 1, 1, 1, NULL, NULL, NULL, NULL);
+        INSERT INTO items VALUES (1, 1, 'file', 0, 'Regular', 1, NULL, NULL, NULL, NULL, 7, NULL);
+        INSERT INTO hashes VALUES (1, 1, 'md5', 'd41d8cd98f00b204e9800998ecf8427e');
+        INSERT INTO metas VALUES (1, 1, 'title', 'tag');
+        PRAGMA user_version = 1;
+    ";
+
+    // Removed before and after, so that a failed run leaves nothing for the next
+    struct TempCatalog(PathBuf);
+
+    impl TempCatalog {
+        fn new(name: &str) -> Self {
+            let path = std::env::temp_dir().join(format!("catalog-test-{}-{}.sqlite", std::process::id(), name));
+            let _ = std::fs::remove_file(&path);
+            Self(path)
+        }
+    }
+
+    impl Drop for TempCatalog {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_file(&self.0);
+        }
+    }
+
+    fn scan() -> Scan {
+        let regular = RegularInfo {
+            md5: "d41d8cd98f00b204e9800998ecf8427e".to_owned(),
+            size: Some(3),
+            inode: Some((1, 2)),
+            metas: vec![("title".to_owned(), "tag".to_owned())],
+            media: Some(MediaInfo { duration: Some(10), video_codec: Some("h264".to_owned()), ..Default::default() }),
+            probe_error: None
+        };
+        let dir = DirInfo { hash: Some("merkle".to_owned()), ..DirInfo::new(1, 3, true) };
+        let symlink = SymlinkInfo { target: Some(1), link: Some("file".to_owned()), broken: Some(false) };
+        let inaccessible = InaccessibleInfo { file_type: FileType::Dir, reason: Some("denied".to_owned()) };
+        let mode = Some(FileMode { mode: 0o644, uid: 1, gid: 2, orphan: false });
+        let times = |modified| FileTimes { modified: Some(modified), ..Default::default() };
+        let items = vec![
+            FileItem::new(0, String::new(), None, SpecificInfo::Dir(dir), times(1), mode),
+            FileItem::new(1, "file".to_owned(), Some(0), SpecificInfo::Regular(regular), times(2), mode),
+            FileItem::new(2, "link".to_owned(), Some(0), SpecificInfo::Symlink(symlink), times(3), None),
+            FileItem::new(3, "locked".to_owned(), Some(0), SpecificInfo::Inaccessible(inaccessible), times(4), None),
+        ];
+        Scan { items, root: "/root".to_owned(), description: "test".to_owned(), warning: "limited".to_owned(), time: Some(9) }
+    }
+
+    fn value(scan: &Scan) -> serde_json::Value {
+        serde_json::to_value(scan).unwrap()
+    }
+
+    #[test]
+    fn round_trip() {
+        let temp = TempCatalog::new("round-trip");
+        let id = Catalog::open_to_insert(&temp.0).unwrap().insert(&scan()).unwrap();
+        let catalog = Catalog::open(&temp.0).unwrap();
+        assert_eq!(value(&catalog.load(id).unwrap()), value(&scan()));
+        assert_eq!(catalog.scans().unwrap().iter().map(|scan| (scan.id, scan.created)).collect::<Vec<_>>(), [(id, 9)]);
+        assert_eq!(catalog.dir_hashes(id).unwrap(), [(0, "merkle".to_owned())]);
+    }
+
+    #[test]
+    fn first_schema() {
+        let temp = TempCatalog::new("first-schema");
+        Connection::open(&temp.0).unwrap().execute_batch(FIRST_SCHEMA).unwrap();
+        // Opened to browse, the older catalog is read as it is
+        let loaded = Catalog::open(&temp.0).unwrap().load(1).unwrap();
+        assert_eq!((loaded.root.as_str(), loaded.description.as_str(), loaded.time), ("", "first", Some(5)));
+        let SpecificInfo::Dir(dir) = loaded.items[0].info() else { panic!("not a directory") };
+        assert!(!dir.truncated && dir.hash.is_none());
+        let SpecificInfo::Regular(regular) = loaded.items[1].info() else { panic!("not a regular file") };
+        assert_eq!((regular.size, regular.inode, regular.metas.len()), (None, None, 1));
+        assert!(regular.media.is_none() && loaded.items[1].mode().is_none());
+        let version = |path| Connection::open(path).unwrap().pragma_query_value(None, "user_version", |row| row.get::<_, u32>(0)).unwrap();
+        assert_eq!(version(&temp.0), 1);
+        // Opened to insert, it is upgraded and keeps the older scan
+        let id = Catalog::open_to_insert(&temp.0).unwrap().insert(&scan()).unwrap();
+        assert_eq!(version(&temp.0), SCHEMA_VERSION);
+        let catalog = Catalog::open(&temp.0).unwrap();
+        assert_eq!(value(&catalog.load(1).unwrap()), value(&loaded));
+        assert_eq!(value(&catalog.load(id).unwrap()), value(&scan()));
+    }
+
+    #[test]
+    fn other_files() {
+        let temp = TempCatalog::new("newer");
+        Connection::open(&temp.0).unwrap().execute_batch(&format!("{} PRAGMA user_version = {};", SCHEMA, SCHEMA_VERSION + 1)).unwrap();
+        assert!(matches!(Catalog::open(&temp.0), Err(RecordError::NewerVersion(_))));
+        assert!(matches!(Catalog::open_to_insert(&temp.0), Err(RecordError::NewerVersion(_))));
+        let temp = TempCatalog::new("other");
+        Connection::open(&temp.0).unwrap().execute_batch("CREATE TABLE other (id INTEGER);").unwrap();
+        assert!(matches!(Catalog::open(&temp.0), Err(RecordError::InvalidContent)));
+        assert!(matches!(Catalog::open_to_insert(&temp.0), Err(RecordError::InvalidContent)));
+    }
+}
@@ -0,0 +1,173 @@
+use super::RecordError;
+use crate::scan::{hash_dirs, item_path, DirInfo, FileItem, FileTimes, RegularInfo, Scan, SpecificInfo};
+use std::{
+    collections::{BTreeMap, VecDeque},
+    fs::File,
+    io::{BufRead, BufReader, BufWriter, Write},
+    path::Path,
+};
+
+// Manifests follow `md5sum`: `<digest>  <path>`, and a line starting with `\` has `\\`, `\n` and `\r` escaped in its path
+pub fn export_checksums(scan: &Scan, path: &Path) -> Result<(), RecordError> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    for item in &scan.items {
+        if let Some(md5) = item.md5() {
+            let path = scan.path(item.id());
+            match path.contains(['\\', '\n', '\r']) {
+                true => writeln!(writer, "\\{}  {}", md5, escape(&path))?,
+                false => writeln!(writer, "{}  {}", md5, path)?,
+            }
+        }
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+#[derive(Default)]
+struct Node {
+    md5: Option<String>,
+    childs: BTreeMap<String, Node>
+}
+
+// Builds a scan that only has the digests, with directories made up from the paths
+pub fn import_checksums(path: &Path) -> Result<Scan, RecordError> {
+    let mut root = Node::default();
+    for (number, line) in BufReader::new(File::open(path)?).lines().enumerate() {
+        let line = line.map_err(|_| RecordError::InvalidContent)?;
+        if line.is_empty() {
+            continue;
+        }
+        let (md5, path) = parse_line(&line).map_err(|err| RecordError::Manifest(format!("line {}: {}", number + 1, err)))?;
+        let mut node = &mut root;
+        for name in path.split('/').filter(|name| !name.is_empty() && *name != ".") {
+            node = node.childs.entry(name.to_owned()).or_default();
+        }
+        node.md5 = Some(md5);
+    }
+    let name = path.file_stem().and_then(|name| name.to_str()).unwrap_or_default().to_owned();
+    let mut items: Vec<FileItem> = Vec::new();
+    let mut bfs = VecDeque::from([(root, name, None)]);
+    let mut count = 0;
+    while let Some((node, name, parent)) = bfs.pop_front() {
+        let id = items.len();
+        let info = match (node.md5, node.childs.is_empty()) {
+            (Some(md5), true) => SpecificInfo::Regular(RegularInfo { md5, size: None, inode: None, metas: Vec::new(), media: None, probe_error: None }),
+            (Some(_), false) => {
+                // Named from its parent, as it is not pushed yet
+                let path = match parent.map(|parent| item_path(parent, |id| (items[id].name(), items[id].parent()))) {
+                    Some(parent) if parent != "." => format!("{}/{}", parent, name),
+                    _ => name,
+                };
+                return Err(RecordError::Manifest(format!("`{}` is both a file and a directory", path)));
+            }
+            (None, _) => {
+                let before = count;
+                count += node.childs.len();
+                SpecificInfo::Dir(DirInfo::new(before + 1, count, false))
+            }
+        };
+        bfs.extend(node.childs.into_iter().map(|(name, child)| (child, name, Some(id))));
+        items.push(FileItem::new(id, name, parent, info, FileTimes::default(), None));
+    }
+    hash_dirs(&mut items);
+    // Manifests are usually checked from where they are
+    let root = std::path::absolute(path.parent().unwrap_or(Path::new("."))).unwrap_or_default().to_string_lossy().into_owned();
+    let description = "Imported from a checksum manifest. ".to_owned();
+    Ok(Scan { items, root, description, warning: String::new(), time: None })
+}
+
+fn parse_line(line: &str) -> Result<(String, String), String> {
+    let invalid = || "expected `<md5>  <path>` or `MD5 (<path>) = <md5>`".to_owned();
+    let sha256 = || "SHA-256 digests are not supported, only MD5".to_owned();
+    let (escaped, line) = match line.strip_prefix('\\') {
+        Some(line) => (true, line),
+        None => (false, line),
+    };
+    if line.starts_with("SHA256 (") {
+        return Err(sha256());
+    }
+    // BSD style from `md5sum --tag`: `MD5 (<path>) = <digest>`
+    let (md5, path) = match line.strip_prefix("MD5 (") {
+        Some(rest) => rest.rsplit_once(") = ").map(|(path, md5)| (md5, path)).ok_or_else(invalid)?,
+        None => {
+            let (md5, path) = line.split_once(' ').ok_or_else(invalid)?;
+            (md5, path.strip_prefix([' ', '*']).ok_or_else(invalid)?)
+        }
+    };
+    if !md5.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(invalid());
+    }
+    match md5.len() {
+        32 => (),
+        64 => return Err(sha256()),
+        _ => return Err(invalid()),
+    }
+    let path = match escaped {
+        true => unescape(path).ok_or("invalid escape in the path")?,
+        false => path.to_owned(),
+    };
+    Ok((md5.to_ascii_lowercase(), path))
+}
+
+fn escape(path: &str) -> String {
+    path.replace('\\', "\\\\").replace('\n', "\\n").replace('\r', "\\r")
+}
+
+fn unescape(path: &str) -> Option<String> {
+    let mut result = String::with_capacity(path.len());
+    let mut chars = path.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => result.push(match chars.next()? {
+                '\\' => '\\',
+                'n' => '\n',
+                'r' => '\r',
+                _ => return None,
+            }),
+            c => result.push(c),
+        }
+    }
+    Some(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MD5: &str = "d41d8cd98f00b204e9800998ecf8427e";
+
+    #[test]
+    fn escapes() {
+        for path in ["plain", "back\\slash", "new\nline", "carriage\rreturn", "\\n", "all\\\n\r"] {
+            assert_eq!(unescape(&escape(path)).as_deref(), Some(path));
+        }
+        assert_eq!(escape("a\\b\nc\rd"), "a\\\\b\\nc\\rd");
+        assert_eq!(unescape("a\\t"), None);
+        assert_eq!(unescape("a\\"), None);
+    }
+
+    #[test]
+    fn lines() {
+        let parsed = |path: &str| Ok((MD5.to_owned(), path.to_owned()));
+        assert_eq!(parse_line(&format!("{}  dir/file", MD5)), parsed("dir/file"));
+        assert_eq!(parse_line(&format!("{} *binary", MD5.to_uppercase())), parsed("binary"));
+        assert_eq!(parse_line(&format!("{}  two  spaces", MD5)), parsed("two  spaces"));
+        assert_eq!(parse_line(&format!("MD5 (a) = b) = {}", MD5)), parsed("a) = b"));
+        assert_eq!(parse_line(&format!("\\{}  back\\\\slash", MD5)), parsed("back\\slash"));
+        assert_eq!(parse_line(&format!("\\{}  new\\nline", MD5)), parsed("new\nline"));
+        assert_eq!(parse_line(&format!("\\{}  carriage\\rreturn", MD5)), parsed("carriage\rreturn"));
+        // Without the leading `\` the path is taken as it is
+        assert_eq!(parse_line(&format!("{}  back\\nslash", MD5)), parsed("back\\nslash"));
+    }
+
+    #[test]
+    fn invalid_lines() {
+        let sha256 = "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855";
+        assert!(parse_line(&format!("{}  file", sha256)).unwrap_err().contains("SHA-256"));
+        assert!(parse_line(&format!("SHA256 (file) = {}", sha256)).unwrap_err().contains("SHA-256"));
+        assert!(parse_line(&format!("{} file", MD5)).is_err());
+        assert!(parse_line(&format!("{}  file", &MD5[1..])).is_err());
+        assert!(parse_line("not a digest  file").is_err());
+        assert!(parse_line(&format!("\\{}  bad\\escape", MD5)).is_err());
+    }
+}
@@ -0,0 +1,85 @@
+use super::RecordError;
+use std::{
+    fmt::Display,
+    io::{BufRead, BufReader, Write},
+    path::Path,
+};
+
+const ZSTD_MAGIC: &[u8; 4] = &[0x28, 0xb5, 0x2f, 0xfd];
+const GZIP_MAGIC: &[u8; 2] = &[0x1f, 0x8b];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    Auto,
+    Plain,
+    Zstd,
+    Gzip,
+}
+
+impl Compression {
+    pub const ALL: [Compression; 4] = [Compression::Auto, Compression::Plain, Compression::Zstd, Compression::Gzip];
+
+    pub fn from_extension(path: &Path) -> Self {
+        match path.extension().and_then(|ext| ext.to_str()).map(str::to_ascii_lowercase).as_deref() {
+            Some("zst") => Compression::Zstd,
+            Some("gz") => Compression::Gzip,
+            _ => Compression::Plain,
+        }
+    }
+
+    pub fn resolve(self, path: &Path) -> Self {
+        match self {
+            Compression::Auto => Compression::from_extension(path),
+            other => other,
+        }
+    }
+}
+
+impl Display for Compression {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Compression::Auto => "By extension",
+            Compression::Plain => "No compression",
+            Compression::Zstd => "Zstandard",
+            Compression::Gzip => "Gzip",
+        })
+    }
+}
+
+pub fn compressed_write<W: Write>(
+    writer: W,
+    compression: Compression,
+    write: impl FnOnce(&mut dyn Write) -> Result<(), RecordError>,
+) -> Result<W, RecordError> {
+    match compression {
+        Compression::Zstd => {
+            let mut encoder = zstd::Encoder::new(writer, 0)?;
+            write(&mut encoder)?;
+            Ok(encoder.finish()?)
+        }
+        Compression::Gzip => {
+            let mut encoder = flate2::write::GzEncoder::new(writer, flate2::Compression::default());
+            write(&mut encoder)?;
+            Ok(encoder.finish()?)
+        }
+        _ => {
+            let mut writer = writer;
+            write(&mut writer)?;
+            Ok(writer)
+        }
+    }
+}
+
+pub fn decompressed_read<R: BufRead, T>(
+    mut reader: R,
+    read: impl FnOnce(&mut dyn BufRead) -> Result<T, RecordError>,
+) -> Result<T, RecordError> {
+    let head = reader.fill_buf()?;
+    if head.starts_with(ZSTD_MAGIC) {
+        read(&mut BufReader::new(zstd::Decoder::with_buffer(reader)?))
+    } else if head.starts_with(GZIP_MAGIC) {
+        read(&mut BufReader::new(flate2::bufread::MultiGzDecoder::new(reader)))
+    } else {
+        read(&mut reader)
+    }
+}
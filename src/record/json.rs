@@ -0,0 +1,168 @@
+use super::{Record, RecordError, VERSION, migrate};
+use crate::scan::{FileItem, Scan};
+use serde::{Deserialize, Serialize, de::IgnoredAny};
+use serde_json::Value;
+use std::io::{BufRead, Write};
+
+// A json record is a header line followed by one line per item, so that it is never held as a whole
+const MAX_RESERVED: usize = 1 << 16;
+
+#[derive(Serialize, Deserialize)]
+struct Header {
+    version: u32,
+    count: usize,
+    root: String,
+    description: String,
+    warning: String,
+    time: Option<i64>
+}
+
+#[derive(Deserialize)]
+struct LayoutProbe {
+    version: Option<u32>,
+    scan: Option<IgnoredAny>,
+    items: Option<IgnoredAny>
+}
+
+pub fn write(scan: &Scan, mut writer: impl Write) -> Result<(), RecordError> {
+    let header = Header {
+        version: VERSION,
+        count: scan.items.len(),
+        root: scan.root.clone(),
+        description: scan.description.clone(),
+        warning: scan.warning.clone(),
+        time: scan.time
+    };
+    write_line(&mut writer, &header)?;
+    for item in &scan.items {
+        write_line(&mut writer, item)?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+pub fn read(mut reader: impl BufRead) -> Result<Scan, RecordError> {
+    let mut line = String::new();
+    reader.read_line(&mut line)?;
+    // Earlier records may also be pretty printed over many lines, they are then read as a whole
+    let probe: LayoutProbe = match serde_json::from_str(&line) {
+        Ok(probe) => probe,
+        Err(_) => {
+            reader.read_to_string(&mut line)?;
+            serde_json::from_str(&line).map_err(|_| RecordError::InvalidContent)?
+        }
+    };
+    // Records written before versioning are a bare `Scan` and have no version field
+    let version = probe.version.unwrap_or(0);
+    if version > VERSION {
+        return Err(RecordError::NewerVersion(version));
+    }
+    if probe.scan.is_some() || probe.items.is_some() {
+        return read_document(&line, version);
+    }
+    let header: Header = match version {
+        VERSION => serde_json::from_str(&line).map_err(|_| RecordError::InvalidContent)?,
+        version => {
+            let mut header: Value = serde_json::from_str(&line).map_err(|_| RecordError::InvalidContent)?;
+            migrate::migrate_scan(header.as_object_mut().ok_or(RecordError::InvalidContent)?, version);
+            serde_json::from_value(header).map_err(|_| RecordError::InvalidContent)?
+        }
+    };
+    // The count is not trusted for more than a guess
+    let mut items = Vec::with_capacity(header.count.min(MAX_RESERVED));
+    for _ in 0..header.count {
+        line.clear();
+        reader.read_line(&mut line)?;
+        let item = match version {
+            VERSION => serde_json::from_str::<FileItem>(&line),
+            version => serde_json::from_str::<Value>(&line).and_then(|mut item| {
+                migrate::migrate_item(&mut item, version);
+                serde_json::from_value(item)
+            }),
+        };
+        items.push(item.map_err(|_| RecordError::InvalidContent)?);
+    }
+    Ok(Scan { items, root: header.root, description: header.description, warning: header.warning, time: header.time })
+}
+
+// Records written before streaming are a single json document
+fn read_document(content: &str, version: u32) -> Result<Scan, RecordError> {
+    let record = match version {
+        VERSION => serde_json::from_str::<Record>(content).map_err(|_| RecordError::InvalidContent)?,
+        version => {
+            let value = serde_json::from_str(content).map_err(|_| RecordError::InvalidContent)?;
+            let value = migrate::migrate(value, version)?;
+            serde_json::from_value::<Record>(value).map_err(|_| RecordError::InvalidContent)?
+        }
+    };
+    Ok(record.scan)
+}
+
+fn write_line(mut writer: impl Write, value: &impl Serialize) -> Result<(), RecordError> {
+    serde_json::to_writer(&mut writer, value).map_err(std::io::Error::from)?;
+    writer.write_all(b"\n")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scan::{DirInfo, FileTimes, RegularInfo, SpecificInfo};
+    use serde_json::json;
+
+    fn scan() -> Scan {
+        let regular = RegularInfo { md5: "d41d8cd98f00b204e9800998ecf8427e".to_owned(), size: Some(0), inode: None, metas: Vec::new(), media: None, probe_error: None };
+        let items = vec![
+            FileItem::new(0, String::new(), None, SpecificInfo::Dir(DirInfo::new(1, 1, false)), FileTimes::default(), None),
+            FileItem::new(1, "file".to_owned(), Some(0), SpecificInfo::Regular(regular), FileTimes::default(), None),
+        ];
+        Scan { items, root: "/root".to_owned(), description: "test".to_owned(), warning: String::new(), time: Some(1) }
+    }
+
+    fn value(scan: &Scan) -> Value {
+        serde_json::to_value(scan).unwrap()
+    }
+
+    #[test]
+    fn streamed() {
+        let mut written = Vec::new();
+        write(&scan(), &mut written).unwrap();
+        assert_eq!(written.iter().filter(|byte| **byte == b'\n').count(), 3);
+        assert_eq!(value(&read(written.as_slice()).unwrap()), value(&scan()));
+    }
+
+    #[test]
+    fn pretty_printed() {
+        let record = serde_json::to_string_pretty(&json!({ "version": VERSION, "scan": scan() })).unwrap();
+        assert_eq!(value(&read(record.as_bytes()).unwrap()), value(&scan()));
+        // Before versioning the scan itself was written
+        let mut bare = value(&scan());
+        bare.as_object_mut().unwrap().remove("time");
+        let read = read(serde_json::to_string_pretty(&bare).unwrap().as_bytes()).unwrap();
+        assert_eq!((read.items.len(), read.root.as_str(), read.time), (2, "", None));
+    }
+
+    #[test]
+    fn streamed_older() {
+        let mut lines = vec![json!({ "version": 8, "count": 2, "root": "/root", "description": "test", "warning": "", "time": 1 })];
+        lines.extend(scan().items.iter().map(|item| {
+            let mut item = serde_json::to_value(item).unwrap();
+            item.pointer_mut("/info/Dir").and_then(Value::as_object_mut).map(|dir| dir.remove("hash"));
+            item
+        }));
+        let record: String = lines.iter().map(|line| format!("{}\n", line)).collect();
+        assert_eq!(value(&read(record.as_bytes()).unwrap()), value(&scan()));
+    }
+
+    #[test]
+    fn invalid() {
+        let newer = format!("{}\n", json!({ "version": VERSION + 1, "count": 0, "root": "", "description": "", "warning": "", "time": null }));
+        assert!(matches!(read(newer.as_bytes()), Err(RecordError::NewerVersion(_))));
+        assert!(matches!(read("{ \"version\":".as_bytes()), Err(RecordError::InvalidContent)));
+        // A header promising more items than there are
+        let mut written = Vec::new();
+        write(&scan(), &mut written).unwrap();
+        let truncated: String = String::from_utf8(written).unwrap().lines().take(2).map(|line| format!("{}\n", line)).collect();
+        assert!(read(truncated.as_bytes()).is_err());
+    }
+}
@@ -0,0 +1,176 @@
+use super::{RecordError, VERSION};
+use serde_json::{Map, Value, json};
+
+// The migration at index `n` upgrades a record of version `n` to version `n + 1`.
+// Scan fields and items are upgraded separately so that streamed records are migrated line by line.
+struct Migration {
+    scan: fn(&mut Map<String, Value>),
+    item: fn(&mut Value)
+}
+
+const MIGRATIONS: [Migration; VERSION as usize] = [
+    // Version 1 only put the scan into an envelope with the version
+    Migration { scan: |_| (), item: |_| () },
+    // Version 2 recorded the root, which is unknown for older records
+    Migration { scan: |scan| { scan.insert("root".to_owned(), json!("")); }, item: |_| () },
+    // Version 3 recorded the size of regular files, which is unknown for older records
+    Migration {
+        scan: |_| (),
+        item: |item| {
+            if let Some(Value::Object(regular)) = item.pointer_mut("/info/Regular") {
+                regular.insert("size".to_owned(), Value::Null);
+            }
+        }
+    },
+    // Version 4 recorded the inode of regular files
+    Migration {
+        scan: |_| (),
+        item: |item| {
+            if let Some(Value::Object(regular)) = item.pointer_mut("/info/Regular") {
+                regular.insert("inode".to_owned(), Value::Null);
+            }
+        }
+    },
+    // Version 5 recorded why items are inaccessible, truncated directories and where symlinks point
+    Migration {
+        scan: |_| (),
+        item: |item| {
+            if let Some(inaccessible) = item.pointer_mut("/info/Inaccessible") {
+                *inaccessible = json!({ "file_type": inaccessible.take(), "reason": null });
+            }
+            if let Some(Value::Object(dir)) = item.pointer_mut("/info/Dir") {
+                dir.insert("truncated".to_owned(), json!(false));
+            }
+            if let Some(Value::Object(symlink)) = item.pointer_mut("/info/Symlink") {
+                symlink.insert("link".to_owned(), Value::Null);
+                symlink.insert("broken".to_owned(), Value::Null);
+            }
+        }
+    },
+    // Version 6 recorded media streams and ffmpeg failures, which were taken for no tags before
+    Migration {
+        scan: |_| (),
+        item: |item| {
+            if let Some(Value::Object(regular)) = item.pointer_mut("/info/Regular") {
+                regular.insert("media".to_owned(), Value::Null);
+                regular.insert("probe_error".to_owned(), Value::Null);
+            }
+        }
+    },
+    // Version 7 recorded when the scan began
+    Migration { scan: |scan| { scan.insert("time".to_owned(), Value::Null); }, item: |_| () },
+    // Version 8 recorded permissions and owners
+    Migration {
+        scan: |_| (),
+        item: |item| {
+            if let Value::Object(item) = item {
+                item.insert("mode".to_owned(), Value::Null);
+            }
+        }
+    },
+    // Version 9 recorded Merkle hashes of directories
+    Migration {
+        scan: |_| (),
+        item: |item| {
+            if let Some(Value::Object(dir)) = item.pointer_mut("/info/Dir") {
+                dir.insert("hash".to_owned(), Value::Null);
+            }
+        }
+    },
+];
+
+pub fn migrate_scan(scan: &mut Map<String, Value>, from: u32) {
+    for migration in &MIGRATIONS[from as usize..] {
+        (migration.scan)(scan);
+    }
+}
+
+pub fn migrate_item(item: &mut Value, from: u32) {
+    for migration in &MIGRATIONS[from as usize..] {
+        (migration.item)(item);
+    }
+}
+
+pub fn migrate(mut record: Value, from: u32) -> Result<Value, RecordError> {
+    let scan = match from {
+        0 => record,
+        _ => record.get_mut("scan").map(Value::take).ok_or(RecordError::InvalidContent)?,
+    };
+    let Value::Object(mut scan) = scan else {
+        return Err(RecordError::InvalidContent);
+    };
+    let mut items = scan.remove("items").ok_or(RecordError::InvalidContent)?;
+    migrate_scan(&mut scan, from);
+    for item in items.as_array_mut().ok_or(RecordError::InvalidContent)? {
+        migrate_item(item, from);
+    }
+    scan.insert("items".to_owned(), items);
+    log::info!("Record migrated from version {} to {}", from, VERSION);
+    Ok(json!({ "version": VERSION, "scan": scan }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::record::Record;
+    use crate::scan::{FileType, SpecificInfo};
+
+    // A version 0 record is the bare scan, without the root and the time
+    fn first_scan() -> Value {
+        let times = json!({ "created": null, "modified": 1, "accessed": null });
+        json!({
+            "description": "old",
+            "warning": "",
+            "items": [
+                { "id": 0, "name": "", "parent": null, "info": { "Dir": { "childs": { "start": 1, "end": 3 } } }, "times": times },
+                { "id": 1, "name": "file", "parent": 0, "info": { "Regular": { "md5": "d41d8cd98f00b204e9800998ecf8427e", "metas": [] } }, "times": times },
+                { "id": 2, "name": "link", "parent": 0, "info": { "Symlink": { "target": 1 } }, "times": times },
+                { "id": 3, "name": "locked", "parent": 0, "info": { "Inaccessible": "Regular" }, "times": times }
+            ]
+        })
+    }
+
+    #[test]
+    fn from_first_version() {
+        let value = migrate(first_scan(), 0).unwrap();
+        assert_eq!(value["version"], json!(VERSION));
+        let scan = serde_json::from_value::<Record>(value).unwrap().scan;
+        assert_eq!((scan.root.as_str(), scan.description.as_str(), scan.time), ("", "old", None));
+        assert_eq!(scan.items.len(), 4);
+        let SpecificInfo::Dir(dir) = scan.items[0].info() else { panic!("not a directory") };
+        assert_eq!((dir.childs.clone(), dir.truncated, dir.hash.as_deref()), (1..=3, false, None));
+        let SpecificInfo::Regular(regular) = scan.items[1].info() else { panic!("not a regular file") };
+        assert_eq!((regular.size, regular.inode), (None, None));
+        assert!(regular.media.is_none() && regular.probe_error.is_none());
+        let SpecificInfo::Symlink(symlink) = scan.items[2].info() else { panic!("not a symlink") };
+        assert_eq!((symlink.target, symlink.link.as_deref(), symlink.broken), (Some(1), None, None));
+        let SpecificInfo::Inaccessible(inaccessible) = scan.items[3].info() else { panic!("not inaccessible") };
+        assert!(inaccessible.file_type == FileType::Regular && inaccessible.reason.is_none());
+        assert!(scan.items.iter().all(|item| item.mode().is_none()));
+    }
+
+    #[test]
+    fn from_every_version() {
+        let current = migrate(first_scan(), 0).unwrap();
+        let mut scan = first_scan();
+        for version in 0..VERSION {
+            let record = match version {
+                0 => scan.clone(),
+                _ => json!({ "version": version, "scan": scan }),
+            };
+            assert_eq!(migrate(record, version).unwrap(), current);
+            // One migration at a time ends the same as all of them at once
+            let migration = &MIGRATIONS[version as usize];
+            (migration.scan)(scan.as_object_mut().unwrap());
+            scan["items"].as_array_mut().unwrap().iter_mut().for_each(migration.item);
+        }
+        assert_eq!(json!({ "version": VERSION, "scan": scan }), current);
+    }
+
+    #[test]
+    fn invalid() {
+        assert!(migrate(json!([]), 0).is_err());
+        assert!(migrate(json!({ "description": "", "warning": "" }), 0).is_err());
+        assert!(migrate(first_scan(), 1).is_err());
+    }
+}
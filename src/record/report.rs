@@ -0,0 +1,35 @@
+use super::RecordError;
+use serde::Serialize;
+use std::{fs::File, io::{BufWriter, Write}, path::Path};
+
+// A report that can also be exported as a flat table
+pub trait TableReport: Serialize {
+    const TITLES: &'static [&'static str];
+
+    fn rows(&self) -> impl Iterator<Item = Vec<String>> + '_;
+}
+
+pub fn export_json(report: &impl Serialize, path: &Path) -> Result<(), RecordError> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    serde_json::to_writer_pretty(&mut writer, report).map_err(std::io::Error::from)?;
+    writer.flush()?;
+    Ok(())
+}
+
+// Exports as CSV or TSV by the extension, or json otherwise
+pub fn export_report(report: &impl TableReport, path: &Path) -> Result<(), RecordError> {
+    fn export_table<R: TableReport>(report: &R, path: &Path, delimiter: u8) -> Result<(), RecordError> {
+        let mut writer = csv::WriterBuilder::new().delimiter(delimiter).from_writer(BufWriter::new(File::create(path)?));
+        writer.write_record(R::TITLES)?;
+        for row in report.rows() {
+            writer.write_record(row)?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+    match path.extension().and_then(|ext| ext.to_str()).map(str::to_ascii_lowercase).as_deref() {
+        Some("csv") => export_table(report, path, b','),
+        Some("tsv") => export_table(report, path, b'\t'),
+        _ => export_json(report, path),
+    }
+}
@@ -0,0 +1,23 @@
+use super::RecordError;
+use crate::scan::{FileInfo, PlainFileItem, Scan, info_string};
+use std::{fs::File, io::BufWriter, path::Path};
+
+impl From<csv::Error> for RecordError {
+    fn from(value: csv::Error) -> Self {
+        RecordError::Io(value.to_string())
+    }
+}
+
+pub fn export_table(scan: &Scan, path: &Path, infos: &[FileInfo]) -> Result<(), RecordError> {
+    let delimiter = match path.extension().and_then(|ext| ext.to_str()).map(str::to_ascii_lowercase).as_deref() {
+        Some("tsv") => b'\t',
+        _ => b',',
+    };
+    let mut writer = csv::WriterBuilder::new().delimiter(delimiter).from_writer(BufWriter::new(File::create(path)?));
+    writer.write_record(std::iter::once("Path").chain(infos.iter().map(FileInfo::title)))?;
+    for PlainFileItem { path, item } in scan.plain_items() {
+        writer.write_record(std::iter::once(path).chain(infos.iter().map(|info| info_string(item, info))))?;
+    }
+    writer.flush()?;
+    Ok(())
+}
@@ -1,21 +1,67 @@
 mod file_item;
 mod file_info;
+mod file_mode;
 mod file_times;
+mod merkle;
+mod name_index;
+mod query;
 mod specific_info;
-pub use file_item::{FileItem, FileType};
-pub use file_info::{FileInfo, info_string};
+pub use file_item::{FileItem, FileType, PlainFileItem};
+pub use file_info::{FileInfo, info_string, stringify_time};
+pub use file_mode::{FileMode, SETGID, SETUID, STICKY};
 pub use file_times::FileTimes;
-pub use specific_info::{DirInfo, RegularInfo, SpecificInfo, SymlinkInfo};
+pub use merkle::hash_dirs;
+pub use name_index::{NameIndex, NameSearch};
+pub use query::Query;
+pub use specific_info::{DirInfo, InaccessibleInfo, MediaInfo, RegularInfo, SpecificInfo, SymlinkInfo};
 
 use file_item::FileItemBuilder;
 use serde::{Serialize, Deserialize};
-use std::{collections::HashMap, path::{Path, PathBuf}};
+use std::{collections::HashMap, path::{Component, Path, PathBuf}};
 
 #[derive(Serialize, Deserialize)]
 pub struct Scan {
+    // Breadth first, so every child comes after its parent
     pub items: Vec<FileItem>,
+    pub root: String,
     pub description: String,
-    pub warning: String
+    pub warning: String,
+    // When the scan began in nanoseconds, unknown for older records
+    pub time: Option<i64>
+}
+
+// Bytes of the regular files and number of items in a subtree, both with its top item
+#[derive(Debug, Clone, Copy)]
+pub struct Subtree {
+    pub size: u64,
+    pub count: u64
+}
+
+impl Scan {
+    pub fn path(&self, id: usize) -> String {
+        item_path(id, |id| (self.items[id].name(), self.items[id].parent()))
+    }
+
+    // When the scan began, or now for older records
+    pub fn time_or_now(&self) -> Option<i64> {
+        self.time.or(chrono::Utc::now().timestamp_nanos_opt())
+    }
+
+    // Summed up from the last item, so that children are done before their parent
+    pub fn subtrees(&self) -> Vec<Subtree> {
+        let mut subtrees: Vec<_> = self.items.iter().map(|item| Subtree { size: item.size().unwrap_or_default(), count: 1 }).collect();
+        for item in self.items.iter().rev() {
+            if let Some(parent) = item.parent() {
+                subtrees[parent].size += subtrees[item.id()].size;
+                subtrees[parent].count += subtrees[item.id()].count;
+            }
+        }
+        subtrees
+    }
+
+    pub fn plain_items(&self) -> impl Iterator<Item = PlainFileItem<'_>> {
+        self.items.iter().map(|item| PlainFileItem { path: self.path(item.id()), item })
+    }
 }
 
 pub struct ScanSettings {
@@ -32,11 +78,26 @@ impl Default for ScanSettings {
     }
 }
 
+// The path of an item from the root, `.` for the root itself, given the name and the parent of any item
+pub fn item_path<'a>(id: usize, link: impl Fn(usize) -> (&'a str, Option<usize>)) -> String {
+    let mut names = Vec::new();
+    let mut item = link(id);
+    while let (name, Some(parent)) = item {
+        names.push(name);
+        item = link(parent);
+    }
+    match names.is_empty() {
+        true => ".".to_owned(),
+        false => names.into_iter().rev().collect::<Vec<_>>().join("/"),
+    }
+}
+
 pub fn scan(scan_path: &Path, settings: &ScanSettings) -> Result<Scan, String> {
+    let time = chrono::Utc::now().timestamp_nanos_opt();
     let mut bfs = vec![0];
     let mut items = vec![FileItemBuilder::new(0, scan_path.to_path_buf())];
     let mut count = 0;
-    let mut path_to_id: HashMap<PathBuf, usize> = HashMap::new();
+    let mut path_to_id: HashMap<PathBuf, usize> = HashMap::from([(normalize(scan_path), 0)]);
     // Message related
     let mut inaccessible = 0;
     let mut limit_reached = false;
@@ -47,7 +108,7 @@ pub fn scan(scan_path: &Path, settings: &ScanSettings) -> Result<Scan, String> {
             let err = read.unwrap_err();
             if err.kind() == std::io::ErrorKind::PermissionDenied {
                 inaccessible += 1;
-                items[id].info(SpecificInfo::Inaccessible(FileType::Dir));
+                items[id].info(SpecificInfo::Inaccessible(InaccessibleInfo { file_type: FileType::Dir, reason: Some(err.to_string()) }));
                 continue;
             } else {
                 return Err(err.to_string());
@@ -55,62 +116,71 @@ pub fn scan(scan_path: &Path, settings: &ScanSettings) -> Result<Scan, String> {
         }
         let dir = read.unwrap();
         let before = count;
+        let mut truncated = false;
         for entry in dir {
             if entry.is_err() {
                 return Err(entry.unwrap_err().to_string());
             }
             let path = entry.unwrap().path();
-            count += 1;
-            if settings.scan_limit.is_some_and(|limit| count >= limit) {
+            if settings.scan_limit.is_some_and(|limit| count + 1 >= limit) {
                 limit_reached = true;
+                truncated = true;
                 break;
             }
+            count += 1;
             let child_id = count;
-            path_to_id.insert(path.clone(), child_id);
+            path_to_id.insert(normalize(&path), child_id);
             if path.is_symlink() {
                 items.push(FileItemBuilder::new(child_id, path));
-                items[child_id].info(SpecificInfo::Symlink(SymlinkInfo { target: None }));
+                items[child_id].info(SpecificInfo::Symlink(SymlinkInfo { target: None, link: None, broken: None }));
             } else if path.is_dir() {
                 bfs.push(child_id);
                 items.push(FileItemBuilder::new(child_id, path));
             } else {
+                items.push(FileItemBuilder::new(child_id, path.clone()));
                 let info = match std::fs::read(&path) {
                     Err(err) => if err.kind() == std::io::ErrorKind::PermissionDenied {
                         inaccessible += 1;
-                        SpecificInfo::Inaccessible(FileType::Regular)
+                        SpecificInfo::Inaccessible(InaccessibleInfo { file_type: FileType::Regular, reason: Some(err.to_string()) })
                     } else {
                         return Err(err.to_string());
                     }
                     Ok(content) => {
+                        let size = Some(content.len() as u64);
+                        let inode = inode(&path);
                         let md5 = format!("{:?}", md5::compute(content));
-                        let metas = if !settings.ffsm { 
-                            Vec::new()
-                        } else {
-                            ez_ffmpeg::container_info::get_metadata(path.to_str().unwrap().to_owned()).unwrap_or_default()
+                        let (metas, media, probe_error) = match settings.ffsm.then(|| probe(&path)) {
+                            None => (Vec::new(), None, None),
+                            Some(Ok((metas, media))) => (metas, Some(media), None),
+                            Some(Err(err)) => (Vec::new(), None, Some(err)),
                         };
-                        SpecificInfo::Regular(RegularInfo { md5, metas })
+                        SpecificInfo::Regular(RegularInfo { md5, size, inode, metas, media, probe_error })
                     }
                 };
-                items.push(FileItemBuilder::new(child_id, path));
                 items[child_id].info(info);
             }
             items[child_id].parent(id);
         }
         let after = count;
-        items[id].info(SpecificInfo::Dir(DirInfo::new(before + 1, after)));
+        items[id].info(SpecificInfo::Dir(DirInfo::new(before + 1, after, truncated)));
     }
     for item in &mut items {
         if let Some(SpecificInfo::Symlink(_)) = item.info {
             let new_info = match std::fs::read_link(&item.path) {
-                Ok(path) => SpecificInfo::Symlink(SymlinkInfo {
-                    target: path_to_id.get(&path).cloned()
+                // A relative link is relative to the directory holding it
+                Ok(link) => SpecificInfo::Symlink(SymlinkInfo {
+                    target: path_to_id.get(&normalize(&item.path.parent().unwrap_or(scan_path).join(&link))).cloned(),
+                    link: Some(link.to_string_lossy().into_owned()),
+                    broken: Some(!item.path.exists())
                 }),
-                Err(_) => SpecificInfo::Inaccessible(FileType::Symlink)
+                Err(err) => SpecificInfo::Inaccessible(InaccessibleInfo { file_type: FileType::Symlink, reason: Some(err.to_string()) })
             };
             item.info(new_info);
         }
     }
-    let items = items.into_iter().map(|builder| builder.build()).collect();
+    let users = file_mode::users();
+    let mut items: Vec<_> = items.into_iter().map(|builder| builder.build(users.as_ref())).collect();
+    hash_dirs(&mut items);
     let mut warning = String::new();
     if limit_reached != false {
         warning += "Limit reached. ";
@@ -120,5 +190,57 @@ pub fn scan(scan_path: &Path, settings: &ScanSettings) -> Result<Scan, String> {
     }
     let description = format!("FFSM {}. ", if settings.ffsm { "on" } else { "off" });
     log::info!("{}", warning);
-    Ok(Scan { items, warning, description })
+    let root = std::path::absolute(scan_path).unwrap_or(scan_path.to_path_buf()).to_string_lossy().into_owned();
+    Ok(Scan { items, root, warning, description, time })
+}
+
+// Failing to read the container is kept apart from having no tags, the streams are optional.
+// The file is opened once for everything, as each open reads and probes it again
+fn probe(path: &Path) -> Result<(Vec<(String, String)>, MediaInfo), String> {
+    use ffmpeg_next::{codec, format, media::Type};
+    let input = format::input(path).map_err(|err| err.to_string())?;
+    let metas = input.metadata().iter().map(|(key, value)| (key.to_owned(), value.to_owned())).collect();
+    let mut media = MediaInfo {
+        duration: Some(input.duration()).filter(|duration| *duration > 0),
+        ..Default::default()
+    };
+    if let Some(stream) = input.streams().best(Type::Video) {
+        media.video_codec = Some(stream.parameters().id().name().to_owned());
+        // The dimensions need a decoder, which ffmpeg may not have for a codec it only names
+        if let Ok(video) = codec::context::Context::from_parameters(stream.parameters()).and_then(|context| context.decoder().video()) {
+            media.width = Some(video.width());
+            media.height = Some(video.height());
+        }
+    }
+    if let Some(stream) = input.streams().best(Type::Audio) {
+        media.audio_codec = Some(stream.parameters().id().name().to_owned());
+    }
+    Ok((metas, media))
+}
+
+// Absolute without `.` and `..`, links are not followed so that paths are compared as they are scanned
+fn normalize(path: &Path) -> PathBuf {
+    let path = std::path::absolute(path).unwrap_or(path.to_path_buf());
+    let mut normal = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => (),
+            Component::ParentDir => {
+                normal.pop();
+            }
+            component => normal.push(component),
+        }
+    }
+    normal
+}
+
+#[cfg(unix)]
+fn inode(path: &Path) -> Option<(u64, u64)> {
+    use std::os::unix::fs::MetadataExt;
+    path.metadata().ok().map(|meta| (meta.dev(), meta.ino()))
+}
+
+#[cfg(not(unix))]
+fn inode(_: &Path) -> Option<(u64, u64)> {
+    None
 }
@@ -1,15 +1,37 @@
 use super::{FileItem, FileType};
 
+#[derive(Debug, Clone, PartialEq)]
 pub enum FileInfo {
     Name,
     Type,
+    // The recorded size of regular files
+    Size,
     Created,
     Modified,
     Accessed,
     Md5,
+    Mode,
     Media(String)
 }
 
+impl FileInfo {
+    pub const FIXED: [FileInfo; 8] = [FileInfo::Name, FileInfo::Type, FileInfo::Size, FileInfo::Created, FileInfo::Modified, FileInfo::Accessed, FileInfo::Md5, FileInfo::Mode];
+
+    pub fn title(&self) -> &str {
+        match self {
+            FileInfo::Name => "Name",
+            FileInfo::Type => "Type",
+            FileInfo::Size => "Size",
+            FileInfo::Created => "Created",
+            FileInfo::Modified => "Modified",
+            FileInfo::Accessed => "Accessed",
+            FileInfo::Md5 => "MD5",
+            FileInfo::Mode => "Mode",
+            FileInfo::Media(name) => name
+        }
+    }
+}
+
 pub fn info_string(item: &FileItem, name: &FileInfo) -> String {
     match name {
         FileInfo::Name => item.name().to_owned(),
@@ -18,15 +40,17 @@ pub fn info_string(item: &FileItem, name: &FileInfo) -> String {
             FileType::Dir => "Directory",
             FileType::Symlink => "Symbol link"
         }.to_owned(),
+        FileInfo::Size => item.size().map(|size| size.to_string()).unwrap_or_default(),
         FileInfo::Created => stringify_time(item.times().created),
         FileInfo::Modified => stringify_time(item.times().modified),
         FileInfo::Accessed => stringify_time(item.times().accessed),
         FileInfo::Md5 => item.md5().unwrap_or_default(),
+        FileInfo::Mode => item.mode().map(|mode| mode.to_string()).unwrap_or_default(),
         FileInfo::Media(name) => item.meta(name).unwrap_or_default()
     }
 }
 
-fn stringify_time(time: Option<i64>) -> String {
+pub fn stringify_time(time: Option<i64>) -> String {
     time.map(|time| chrono::DateTime::from_timestamp_nanos(time)
         .format("%Y/%m/%d %H:%M:%S").to_string())
         .unwrap_or_default()
@@ -1,9 +1,9 @@
-use super::FileTimes;
+use super::{FileMode, FileTimes};
 use super::specific_info::SpecificInfo;
 use serde::{Deserialize, Serialize};
-use std::{ops::RangeInclusive, path::PathBuf};
+use std::{collections::HashSet, ops::RangeInclusive, path::PathBuf};
 
-#[derive(Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
 pub enum FileType {
     Regular,
     Dir,
@@ -17,9 +17,14 @@ pub struct FileItem {
     parent: Option<usize>,
     info: SpecificInfo,
     times: FileTimes,
+    // Unknown for records before version 8 and off unix
+    mode: Option<FileMode>,
 }
 
 impl FileItem {
+    pub fn new(id: usize, name: String, parent: Option<usize>, info: SpecificInfo, times: FileTimes, mode: Option<FileMode>) -> Self {
+        Self { id, name, parent, info, times, mode }
+    }
     pub fn id(&self) -> usize {
         self.id.clone()
     }
@@ -32,12 +37,21 @@ impl FileItem {
     pub fn times(&self) -> &FileTimes {
         &self.times
     }
+    pub fn info(&self) -> &SpecificInfo {
+        &self.info
+    }
+    pub(super) fn info_mut(&mut self) -> &mut SpecificInfo {
+        &mut self.info
+    }
+    pub fn mode(&self) -> Option<FileMode> {
+        self.mode
+    }
     pub fn file_type(&self) -> FileType {
         match &self.info {
             SpecificInfo::Regular(_) => FileType::Regular,
             SpecificInfo::Dir(_) => FileType::Dir,
             SpecificInfo::Symlink(_) => FileType::Symlink,
-            SpecificInfo::Inaccessible(file_type) => file_type.clone(),
+            SpecificInfo::Inaccessible(inaccessible) => inaccessible.file_type.clone(),
         }
     }
     pub fn is_dir(&self) -> bool {
@@ -55,6 +69,24 @@ impl FileItem {
         }
         None
     }
+    pub fn size(&self) -> Option<u64> {
+        if let SpecificInfo::Regular(regular) = &self.info {
+            return regular.size;
+        }
+        None
+    }
+    pub fn dir_hash(&self) -> Option<&str> {
+        if let SpecificInfo::Dir(dir) = &self.info {
+            return dir.hash.as_deref();
+        }
+        None
+    }
+    pub fn inode(&self) -> Option<(u64, u64)> {
+        if let SpecificInfo::Regular(regular) = &self.info {
+            return regular.inode;
+        }
+        None
+    }
     pub fn meta(&self, name: &str) -> Option<String> {
         if let SpecificInfo::Regular(regular) = &self.info {
             for meta in &regular.metas {
@@ -72,15 +104,23 @@ pub struct FileItemBuilder {
     pub path: PathBuf,
     pub parent: Option<usize>,
     pub info: Option<SpecificInfo>,
+    pub times: FileTimes,
+    pub mode: Option<FileMode>,
 }
 
 impl FileItemBuilder {
+    // Times are taken before the scan reads the item, which may update the access time
     pub fn new(id: usize, path: PathBuf) -> Self {
+        let meta = path.metadata().ok();
+        let times = meta.clone().map(|meta| FileTimes::from(meta)).unwrap_or_default();
+        let mode = meta.and_then(|meta| FileMode::new(&meta));
         Self {
             id: id,
             path: path,
             parent: None,
             info: None,
+            times: times,
+            mode: mode,
         }
     }
     pub fn parent(&mut self, parent: usize) {
@@ -89,23 +129,21 @@ impl FileItemBuilder {
     pub fn info(&mut self, info: SpecificInfo) {
         self.info = Some(info);
     }
-    pub fn build(self) -> FileItem {
+    // Owners are orphans unless they are in `users`, if it is known
+    pub fn build(self, users: Option<&HashSet<u32>>) -> FileItem {
         let name = self.path.file_name().unwrap().to_str().unwrap().to_owned();
-        let times = self.path.metadata().ok().map(|meta| FileTimes::from(meta)).unwrap_or_default();
         FileItem {
             id: self.id,
             name: name,
             parent: self.parent,
             info: self.info.expect("[FileItemBuilder] `info` is required field"),
-            times: times,
+            times: self.times,
+            mode: self.mode.map(|mode| FileMode { orphan: users.is_some_and(|users| !users.contains(&mode.uid)), ..mode }),
         }
     }
 }
 
-#[derive(Serialize, Deserialize)]
-pub struct PlainFileItem {
-    id: usize,
-    path: String,
-    file_type: FileType,
-    times: FileTimes,
+pub struct PlainFileItem<'a> {
+    pub path: String,
+    pub item: &'a FileItem,
 }
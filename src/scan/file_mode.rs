@@ -0,0 +1,61 @@
+use serde::{Deserialize, Serialize};
+use std::{collections::HashSet, fmt::Display};
+
+pub const SETUID: u32 = 0o4000;
+pub const SETGID: u32 = 0o2000;
+pub const STICKY: u32 = 0o1000;
+
+#[derive(Clone, Copy, Serialize, Deserialize)]
+pub struct FileMode {
+    // Permission bits with setuid, setgid and sticky
+    pub mode: u32,
+    pub uid: u32,
+    pub gid: u32,
+    // The uid has no user on the scanned system
+    pub orphan: bool
+}
+
+impl FileMode {
+    #[cfg(unix)]
+    pub fn new(meta: &std::fs::Metadata) -> Option<Self> {
+        use std::os::unix::fs::MetadataExt;
+        Some(Self { mode: meta.mode() & 0o7777, uid: meta.uid(), gid: meta.gid(), orphan: false })
+    }
+
+    #[cfg(not(unix))]
+    pub fn new(_: &std::fs::Metadata) -> Option<Self> {
+        None
+    }
+
+    // Like `ls -l`, without the type
+    pub fn permissions(&self) -> String {
+        let special = [(SETUID, 's', 2), (SETGID, 's', 5), (STICKY, 't', 8)];
+        let mut chars: Vec<char> = "rwxrwxrwx".chars().enumerate()
+            .map(|(i, char)| if self.mode & (0o400 >> i) != 0 { char } else { '-' })
+            .collect();
+        for (bit, char, i) in special {
+            if self.mode & bit != 0 {
+                chars[i] = if chars[i] == 'x' { char } else { char.to_ascii_uppercase() };
+            }
+        }
+        chars.into_iter().collect()
+    }
+}
+
+impl Display for FileMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}:{}", self.permissions(), self.uid, self.gid)
+    }
+}
+
+// Uids in /etc/passwd, users only known through NSS like LDAP are missed
+#[cfg(unix)]
+pub fn users() -> Option<HashSet<u32>> {
+    let passwd = std::fs::read_to_string("/etc/passwd").ok()?;
+    Some(passwd.lines().filter_map(|line| line.split(':').nth(2)?.parse().ok()).collect())
+}
+
+#[cfg(not(unix))]
+pub fn users() -> Option<HashSet<u32>> {
+    None
+}
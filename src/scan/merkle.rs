@@ -0,0 +1,116 @@
+use super::{FileItem, SpecificInfo};
+use std::ops::RangeInclusive;
+
+// The hash of a directory covers the names, types and hashes of its children sorted by name,
+// so directories with the same hash have the same contents.
+// Unknown when anything inside is inaccessible or left out by the scan limit.
+pub fn hash_dirs(items: &mut [FileItem]) {
+    // Children are hashed before their parent
+    for id in (0..items.len()).rev() {
+        let SpecificInfo::Dir(dir) = items[id].info() else {
+            continue;
+        };
+        let hash = match dir.truncated {
+            true => None,
+            false => dir_hash(items, dir.childs.clone()),
+        };
+        if let SpecificInfo::Dir(dir) = items[id].info_mut() {
+            dir.hash = hash;
+        }
+    }
+}
+
+fn dir_hash(items: &[FileItem], childs: RangeInclusive<usize>) -> Option<String> {
+    let mut entries = Vec::new();
+    for child in &items[childs] {
+        let (tag, hash) = match child.info() {
+            SpecificInfo::Regular(regular) => ('f', regular.md5.as_str()),
+            SpecificInfo::Dir(dir) => ('d', dir.hash.as_deref()?),
+            SpecificInfo::Symlink(symlink) => ('l', symlink.link.as_deref()?),
+            SpecificInfo::Inaccessible(_) => return None,
+        };
+        entries.push((child.name(), tag, hash));
+    }
+    entries.sort();
+    let mut context = md5::Context::new();
+    for (name, tag, hash) in entries {
+        // Lengths keep names from running into each other
+        context.consume(format!("{} {} {} {}\n", tag, name.len(), name, hash));
+    }
+    Some(format!("{:?}", context.finalize()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scan::{DirInfo, FileTimes, FileType, InaccessibleInfo, RegularInfo, SymlinkInfo};
+
+    fn item(id: usize, name: &str, parent: Option<usize>, info: SpecificInfo) -> FileItem {
+        FileItem::new(id, name.to_owned(), parent, info, FileTimes::default(), None)
+    }
+
+    fn regular(md5: &str) -> SpecificInfo {
+        SpecificInfo::Regular(RegularInfo { md5: md5.to_owned(), size: None, inode: None, metas: Vec::new(), media: None, probe_error: None })
+    }
+
+    fn dir(start: usize, end: usize) -> SpecificInfo {
+        SpecificInfo::Dir(DirInfo::new(start, end, false))
+    }
+
+    // A root holding a directory with two files, in the given order, and a file beside it
+    fn hashes(first: (&str, &str), second: (&str, &str), inner: SpecificInfo) -> Vec<Option<String>> {
+        let mut items = vec![
+            item(0, "", None, dir(1, 2)),
+            item(1, "dir", Some(0), dir(3, 4)),
+            item(2, "beside", Some(0), inner),
+            item(3, first.0, Some(1), regular(first.1)),
+            item(4, second.0, Some(1), regular(second.1)),
+        ];
+        hash_dirs(&mut items);
+        items.iter().map(|item| item.dir_hash().map(str::to_owned)).collect()
+    }
+
+    #[test]
+    fn same_contents() {
+        let hashed = hashes(("a", "1"), ("b", "2"), regular("3"));
+        assert!(hashed[0].is_some() && hashed[1].is_some() && hashed[2].is_none());
+        // The order of the children does not matter
+        assert_eq!(hashes(("b", "2"), ("a", "1"), regular("3")), hashed);
+        assert_ne!(hashed[0], hashed[1]);
+        // An empty directory has a hash as well
+        let mut items = vec![item(0, "", None, dir(1, 0))];
+        hash_dirs(&mut items);
+        assert!(items[0].dir_hash().is_some());
+    }
+
+    #[test]
+    fn changed_contents() {
+        let hashed = hashes(("a", "1"), ("b", "2"), regular("3"));
+        for changed in [hashes(("a", "1"), ("b", "9"), regular("3")), hashes(("a", "1"), ("c", "2"), regular("3"))] {
+            assert_ne!(changed[1], hashed[1]);
+            assert_ne!(changed[0], hashed[0]);
+        }
+        // Only the directory holding the change and the ones above it differ
+        let changed = hashes(("a", "1"), ("b", "2"), regular("9"));
+        assert_eq!(changed[1], hashed[1]);
+        assert_ne!(changed[0], hashed[0]);
+        // Names do not run into the hashes
+        assert_ne!(hashes(("a", "1"), ("b", "2"), regular("3")), hashes(("a", "1"), ("b 2", ""), regular("3")));
+        // A symlink and a file with the same content are told apart
+        let link = SpecificInfo::Symlink(SymlinkInfo { target: None, link: Some("3".to_owned()), broken: None });
+        assert_ne!(hashes(("a", "1"), ("b", "2"), link)[0], hashed[0]);
+    }
+
+    #[test]
+    fn unknown() {
+        // Inaccessible items and links of older records leave every directory above them unknown
+        let inaccessible = SpecificInfo::Inaccessible(InaccessibleInfo { file_type: FileType::Regular, reason: None });
+        assert_eq!(hashes(("a", "1"), ("b", "2"), inaccessible)[0], None);
+        let link = SpecificInfo::Symlink(SymlinkInfo { target: None, link: None, broken: None });
+        let hashed = hashes(("a", "1"), ("b", "2"), link);
+        assert!(hashed[0].is_none() && hashed[1].is_some());
+        let mut items = vec![item(0, "", None, dir(1, 1)), item(1, "dir", Some(0), SpecificInfo::Dir(DirInfo::new(2, 1, true)))];
+        hash_dirs(&mut items);
+        assert!(items[0].dir_hash().is_none() && items[1].dir_hash().is_none());
+    }
+}
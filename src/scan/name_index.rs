@@ -0,0 +1,113 @@
+use super::item_path;
+use std::{collections::HashMap, fmt::Display};
+use trie_rs::map::{Trie, TrieBuilder};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NameSearch {
+    Prefix,
+    Substring,
+}
+
+impl NameSearch {
+    pub const ALL: [NameSearch; 2] = [NameSearch::Prefix, NameSearch::Substring];
+}
+
+impl Display for NameSearch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            NameSearch::Prefix => "Starts with",
+            NameSearch::Substring => "Contains",
+        })
+    }
+}
+
+// Items share a lot of names, so every distinct name is stored once with the items having it.
+// The index holds the parents too, as a catalog scan is never in memory as a whole.
+pub struct NameIndex {
+    names: Vec<DistinctName>,
+    items: Vec<(usize, Option<usize>)>,
+    // Lowercase names to the distinct names having them
+    trie: Trie<u8, Vec<usize>>,
+    // Every three bytes of the lowercase names to the distinct names having them, in order
+    trigrams: HashMap<[u8; 3], Vec<u32>>
+}
+
+struct DistinctName {
+    name: String,
+    lowercase: String,
+    ids: Vec<usize>
+}
+
+impl NameIndex {
+    // Items are given in the order of their ids
+    pub fn new(items: impl IntoIterator<Item = (String, Option<usize>)>) -> Self {
+        let mut names: Vec<DistinctName> = Vec::new();
+        let mut distinct: HashMap<String, usize> = HashMap::new();
+        let items = items.into_iter().enumerate().map(|(id, (name, parent))| {
+            let index = *distinct.entry(name).or_insert_with_key(|name| {
+                names.push(DistinctName { name: name.clone(), lowercase: name.to_lowercase(), ids: Vec::new() });
+                names.len() - 1
+            });
+            names[index].ids.push(id);
+            (index, parent)
+        }).collect();
+        let mut lowercases: HashMap<&str, Vec<usize>> = HashMap::new();
+        for (index, name) in names.iter().enumerate() {
+            lowercases.entry(&name.lowercase).or_default().push(index);
+        }
+        let mut builder = TrieBuilder::new();
+        for (lowercase, indices) in lowercases {
+            builder.push(lowercase, indices);
+        }
+        let mut trigrams: HashMap<[u8; 3], Vec<u32>> = HashMap::new();
+        for (index, name) in names.iter().enumerate() {
+            let mut grams: Vec<_> = name.lowercase.as_bytes().windows(3).map(|gram| [gram[0], gram[1], gram[2]]).collect();
+            grams.sort_unstable();
+            grams.dedup();
+            for gram in grams {
+                trigrams.entry(gram).or_default().push(index as u32);
+            }
+        }
+        Self { trie: builder.build(), names, items, trigrams }
+    }
+
+    // At most `limit` items, grouped by name
+    pub fn search(&self, query: &str, search: NameSearch, match_case: bool, limit: usize) -> Vec<usize> {
+        if query.is_empty() {
+            return Vec::new();
+        }
+        let lowercase = query.to_lowercase();
+        let matches = |name: &DistinctName| match (search, match_case) {
+            (NameSearch::Prefix, true) => name.name.starts_with(query),
+            (NameSearch::Prefix, false) => true,
+            (NameSearch::Substring, true) => name.name.contains(query),
+            (NameSearch::Substring, false) => name.lowercase.contains(&lowercase),
+        };
+        // Names containing the query have all of its trigrams, so the rarest one gives the fewest to check.
+        // Shorter queries check every name, but match most of them and stop at the limit early
+        let indices: Box<dyn Iterator<Item = usize>> = match search {
+            NameSearch::Prefix => Box::new(self.trie.predictive_search(&lowercase).flat_map(|(_, indices): (Vec<u8>, &Vec<usize>)| indices.iter().copied())),
+            NameSearch::Substring if lowercase.len() >= 3 => {
+                let rarest = lowercase.as_bytes().windows(3)
+                    .map(|gram| self.trigrams.get(gram).map_or(&[][..], Vec::as_slice))
+                    .min_by_key(|indices| indices.len())
+                    .unwrap_or_default();
+                Box::new(rarest.iter().map(|index| *index as usize))
+            }
+            NameSearch::Substring => Box::new(0..self.names.len()),
+        };
+        indices.map(|index| &self.names[index])
+            .filter(|name| matches(name))
+            .flat_map(|name| name.ids.iter().copied())
+            .take(limit)
+            .collect()
+    }
+
+    pub fn parent(&self, id: usize) -> Option<usize> {
+        self.items[id].1
+    }
+
+    pub fn path(&self, id: usize) -> String {
+        item_path(id, |id| (self.names[self.items[id].0].name.as_str(), self.items[id].1))
+    }
+}
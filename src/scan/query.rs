@@ -0,0 +1,464 @@
+use super::{FileItem, FileType};
+use regex::Regex;
+use std::cmp::Ordering;
+
+// A filter over items, like `type = regular and ext like "mp*" and size > 2GB and modified < 2022 and meta.encoder matches "^Lavf"`.
+// Comparisons are `field op value`, with `=`, `!=`, `<`, `<=`, `>`, `>=`, `like` for globs and `matches` for regexes,
+// and they are combined with `and`, `or`, `not` and parentheses. Items without the field never match a comparison.
+pub struct Query {
+    expr: Expr
+}
+
+enum Expr {
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Not(Box<Expr>),
+    Test(Field, Test)
+}
+
+enum Field {
+    Name,
+    Ext,
+    Type,
+    Size,
+    Created,
+    Modified,
+    Accessed,
+    Md5,
+    Meta(String)
+}
+
+enum Test {
+    Compare(Op, Value),
+    Pattern(Regex)
+}
+
+#[derive(Clone, Copy)]
+enum Op {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge
+}
+
+enum Value {
+    Type(FileType),
+    Number(u64),
+    Time(i64),
+    Text(String)
+}
+
+#[derive(Clone)]
+enum Token {
+    Open,
+    Close,
+    Symbol(String),
+    Word(String),
+    Quoted(String)
+}
+
+impl Query {
+    pub fn parse(source: &str) -> Result<Self, String> {
+        let tokens = tokenize(source)?;
+        let mut parser = Parser { tokens, pos: 0 };
+        let expr = parser.or()?;
+        match parser.peek() {
+            None => Ok(Self { expr }),
+            Some(token) => Err(format!("Unexpected {}", describe(token))),
+        }
+    }
+
+    pub fn matches(&self, item: &FileItem) -> bool {
+        self.expr.matches(item)
+    }
+}
+
+impl Expr {
+    fn matches(&self, item: &FileItem) -> bool {
+        match self {
+            Expr::And(left, right) => left.matches(item) && right.matches(item),
+            Expr::Or(left, right) => left.matches(item) || right.matches(item),
+            Expr::Not(expr) => !expr.matches(item),
+            Expr::Test(field, test) => {
+                let Some(value) = field.value(item) else {
+                    return false;
+                };
+                match test {
+                    Test::Compare(op, expected) => value.compare(expected).is_some_and(|ordering| op.test(ordering)),
+                    Test::Pattern(regex) => match value {
+                        Value::Text(text) => regex.is_match(&text),
+                        _ => false,
+                    },
+                }
+            }
+        }
+    }
+}
+
+impl Field {
+    fn parse(word: &str) -> Result<Self, String> {
+        Ok(match word.to_lowercase().as_str() {
+            "name" => Field::Name,
+            "ext" | "extension" => Field::Ext,
+            "type" => Field::Type,
+            "size" => Field::Size,
+            "created" => Field::Created,
+            "modified" => Field::Modified,
+            "accessed" => Field::Accessed,
+            "md5" => Field::Md5,
+            _ => match word.split_once('.') {
+                Some(("meta", key)) if !key.is_empty() => Field::Meta(key.to_owned()),
+                _ => return Err(format!("Unknown field `{}`, expected name, ext, type, size, created, modified, accessed, md5 or meta.<key>", word)),
+            },
+        })
+    }
+
+    fn value(&self, item: &FileItem) -> Option<Value> {
+        let times = item.times();
+        match self {
+            Field::Name => Some(Value::Text(item.name().to_owned())),
+            Field::Ext => Some(Value::Text(item.name().rsplit_once('.').filter(|(stem, _)| !stem.is_empty()).map(|(_, ext)| ext.to_lowercase()).unwrap_or_default())),
+            Field::Type => Some(Value::Type(item.file_type())),
+            Field::Size => item.size().map(Value::Number),
+            Field::Created => times.created.map(Value::Time),
+            Field::Modified => times.modified.map(Value::Time),
+            Field::Accessed => times.accessed.map(Value::Time),
+            Field::Md5 => item.md5().map(Value::Text),
+            Field::Meta(key) => item.meta(key).map(Value::Text),
+        }
+    }
+
+    // Values are typed by the field they are compared with
+    fn value_of(&self, text: &str) -> Result<Value, String> {
+        match self {
+            Field::Type => match text.to_lowercase().as_str() {
+                "regular" | "file" => Ok(Value::Type(FileType::Regular)),
+                "dir" | "directory" => Ok(Value::Type(FileType::Dir)),
+                "symlink" | "link" => Ok(Value::Type(FileType::Symlink)),
+                _ => Err(format!("Unknown type `{}`, expected regular, dir or symlink", text)),
+            },
+            Field::Size => parse_size(text).map(Value::Number).ok_or_else(|| format!("Invalid size `{}`, expected something like 100, 2GB or 512KiB", text)),
+            Field::Created | Field::Modified | Field::Accessed => parse_time(text).map(Value::Time).ok_or_else(|| format!("Invalid time `{}`, expected something like 2022, 2022-06 or 2022-06-30 12:00:00", text)),
+            Field::Ext => Ok(Value::Text(text.trim_start_matches('.').to_lowercase())),
+            Field::Md5 => Ok(Value::Text(text.to_lowercase())),
+            Field::Name | Field::Meta(_) => Ok(Value::Text(text.to_owned())),
+        }
+    }
+}
+
+impl Value {
+    fn compare(&self, other: &Value) -> Option<Ordering> {
+        match (self, other) {
+            (Value::Type(typea), Value::Type(typeb)) => Some(typea.cmp(typeb)),
+            (Value::Number(numbera), Value::Number(numberb)) => Some(numbera.cmp(numberb)),
+            (Value::Time(timea), Value::Time(timeb)) => Some(timea.cmp(timeb)),
+            // Media metadata is all text, but numbers in it are compared as numbers
+            (Value::Text(texta), Value::Text(textb)) => match (texta.parse::<f64>(), textb.parse::<f64>()) {
+                (Ok(numbera), Ok(numberb)) => numbera.partial_cmp(&numberb),
+                _ => Some(texta.cmp(textb)),
+            },
+            _ => None,
+        }
+    }
+}
+
+impl Op {
+    fn test(self, ordering: Ordering) -> bool {
+        match self {
+            Op::Eq => ordering.is_eq(),
+            Op::Ne => ordering.is_ne(),
+            Op::Lt => ordering.is_lt(),
+            Op::Le => ordering.is_le(),
+            Op::Gt => ordering.is_gt(),
+            Op::Ge => ordering.is_ge(),
+        }
+    }
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Result<Token, String> {
+        let token = self.tokens.get(self.pos).cloned().ok_or("Unexpected end of query")?;
+        self.pos += 1;
+        Ok(token)
+    }
+
+    fn keyword(&mut self, keyword: &str) -> bool {
+        let found = matches!(self.peek(), Some(Token::Word(word)) if word.eq_ignore_ascii_case(keyword));
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn or(&mut self) -> Result<Expr, String> {
+        let mut expr = self.and()?;
+        while self.keyword("or") {
+            expr = Expr::Or(Box::new(expr), Box::new(self.and()?));
+        }
+        Ok(expr)
+    }
+
+    fn and(&mut self) -> Result<Expr, String> {
+        let mut expr = self.not()?;
+        while self.keyword("and") {
+            expr = Expr::And(Box::new(expr), Box::new(self.not()?));
+        }
+        Ok(expr)
+    }
+
+    fn not(&mut self) -> Result<Expr, String> {
+        match self.keyword("not") {
+            true => Ok(Expr::Not(Box::new(self.not()?))),
+            false => self.primary(),
+        }
+    }
+
+    fn primary(&mut self) -> Result<Expr, String> {
+        let field = match self.next()? {
+            Token::Open => {
+                let expr = self.or()?;
+                return match self.next()? {
+                    Token::Close => Ok(expr),
+                    token => Err(format!("Expected `)` but found {}", describe(&token))),
+                };
+            }
+            Token::Word(word) => Field::parse(&word)?,
+            token => return Err(format!("Expected a field but found {}", describe(&token))),
+        };
+        let op = self.next()?;
+        let value = match self.next()? {
+            Token::Word(value) | Token::Quoted(value) => value,
+            token => return Err(format!("Expected a value but found {}", describe(&token))),
+        };
+        let test = match op {
+            Token::Word(word) if word.eq_ignore_ascii_case("like") => {
+                // Extensions and digests are lowercase, like their values
+                let glob = match field {
+                    Field::Ext => value.trim_start_matches('.').to_lowercase(),
+                    Field::Md5 => value.to_lowercase(),
+                    _ => value,
+                };
+                Test::Pattern(Regex::new(&glob_regex(&glob)).map_err(|err| err.to_string())?)
+            }
+            Token::Word(word) if word.eq_ignore_ascii_case("matches") => Test::Pattern(Regex::new(&value).map_err(|err| err.to_string())?),
+            Token::Symbol(symbol) => {
+                let op = match symbol.as_str() {
+                    "=" | "==" => Op::Eq,
+                    "!=" => Op::Ne,
+                    "<" => Op::Lt,
+                    "<=" => Op::Le,
+                    ">" => Op::Gt,
+                    ">=" => Op::Ge,
+                    _ => return Err(format!("Unknown operator `{}`", symbol)),
+                };
+                if matches!(field, Field::Type) && !matches!(op, Op::Eq | Op::Ne) {
+                    return Err("`type` can only be compared with = and !=".to_owned());
+                }
+                Test::Compare(op, field.value_of(&value)?)
+            }
+            token => return Err(format!("Expected an operator but found {}", describe(&token))),
+        };
+        Ok(Expr::Test(field, test))
+    }
+}
+
+fn tokenize(source: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = source.chars().peekable();
+    while let Some(&char) = chars.peek() {
+        match char {
+            char if char.is_whitespace() => {
+                chars.next();
+            }
+            '(' | ')' => {
+                chars.next();
+                tokens.push(if char == '(' { Token::Open } else { Token::Close });
+            }
+            '=' | '!' | '<' | '>' => {
+                let mut symbol = String::new();
+                while let Some(char) = chars.next_if(|char| matches!(char, '=' | '!' | '<' | '>')) {
+                    symbol.push(char);
+                }
+                tokens.push(Token::Symbol(symbol));
+            }
+            '"' | '\'' => {
+                chars.next();
+                let mut quoted = String::new();
+                loop {
+                    match chars.next() {
+                        None => return Err("Unclosed quote".to_owned()),
+                        Some('\\') => quoted.extend(chars.next()),
+                        Some(next) if next == char => break,
+                        Some(next) => quoted.push(next),
+                    }
+                }
+                tokens.push(Token::Quoted(quoted));
+            }
+            _ => {
+                let mut word = String::new();
+                while let Some(char) = chars.next_if(|char| !char.is_whitespace() && !matches!(char, '(' | ')' | '=' | '!' | '<' | '>' | '"' | '\'')) {
+                    word.push(char);
+                }
+                tokens.push(Token::Word(word));
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+fn describe(token: &Token) -> String {
+    match token {
+        Token::Open => "`(`".to_owned(),
+        Token::Close => "`)`".to_owned(),
+        Token::Symbol(text) | Token::Word(text) => format!("`{}`", text),
+        Token::Quoted(text) => format!("\"{}\"", text),
+    }
+}
+
+// `*` is any text, `?` is any character and `[...]` is a character class, the whole value has to match
+fn glob_regex(glob: &str) -> String {
+    let mut regex = String::from("^");
+    let mut chars = glob.chars();
+    while let Some(char) = chars.next() {
+        match char {
+            '*' => regex.push_str(".*"),
+            '?' => regex.push('.'),
+            '[' => {
+                regex.push('[');
+                let mut class = chars.by_ref().take_while(|char| *char != ']').peekable();
+                if class.next_if(|char| *char == '!').is_some() {
+                    regex.push('^');
+                }
+                for char in class {
+                    if matches!(char, '\\' | '[' | '&' | '~') {
+                        regex.push('\\');
+                    }
+                    regex.push(char);
+                }
+                regex.push(']');
+            }
+            char => regex.push_str(&regex::escape(&char.to_string())),
+        }
+    }
+    regex.push('$');
+    regex
+}
+
+fn parse_size(text: &str) -> Option<u64> {
+    let split = text.find(|char: char| char.is_ascii_alphabetic()).unwrap_or(text.len());
+    let (number, unit) = text.split_at(split);
+    let number: f64 = number.trim().parse().ok()?;
+    let unit: f64 = match unit.to_lowercase().as_str() {
+        "" | "b" => 1.,
+        "k" | "kb" => 1e3,
+        "m" | "mb" => 1e6,
+        "g" | "gb" => 1e9,
+        "t" | "tb" => 1e12,
+        "kib" => 1024.,
+        "mib" => 1024f64.powi(2),
+        "gib" => 1024f64.powi(3),
+        "tib" => 1024f64.powi(4),
+        _ => return None,
+    };
+    (number >= 0.).then_some((number * unit) as u64)
+}
+
+// Times are in UTC like the viewer shows them, a partial time is its earliest moment
+fn parse_time(text: &str) -> Option<i64> {
+    let text = text.trim().replace('T', " ");
+    let full = match text.len() {
+        4 => format!("{}-01-01 00:00:00", text),
+        7 => format!("{}-01 00:00:00", text),
+        10 => format!("{} 00:00:00", text),
+        16 => format!("{}:00", text),
+        _ => text,
+    };
+    chrono::NaiveDateTime::parse_from_str(&full, "%Y-%m-%d %H:%M:%S").ok()?.and_utc().timestamp_nanos_opt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scan::{FileTimes, RegularInfo, SpecificInfo};
+
+    fn file(name: &str, size: u64, modified: &str) -> FileItem {
+        let info = RegularInfo { md5: "d41d8cd98f00b204e9800998ecf8427e".to_owned(), size: Some(size), inode: None, metas: vec![("encoder".to_owned(), "Lavf58".to_owned())], media: None, probe_error: None };
+        let times = FileTimes { modified: parse_time(modified), ..Default::default() };
+        FileItem::new(1, name.to_owned(), Some(0), SpecificInfo::Regular(info), times, None)
+    }
+
+    fn matches(source: &str, item: &FileItem) -> bool {
+        Query::parse(source).unwrap().matches(item)
+    }
+
+    #[test]
+    fn precedence() {
+        let item = file("a.mp4", 10, "2020");
+        // `and` binds tighter than `or`
+        assert!(matches("size > 100 and name = x or ext = mp4", &item));
+        assert!(!matches("size > 100 and (name = x or ext = mp4)", &item));
+        assert!(matches("not size > 100 and ext = mp4", &item));
+        assert!(!matches("not (size < 100 and ext = mp4)", &item));
+        assert!(matches("NOT not ext = mp4", &item));
+    }
+
+    #[test]
+    fn sizes() {
+        assert_eq!(parse_size("100"), Some(100));
+        assert_eq!(parse_size("2GB"), Some(2_000_000_000));
+        assert_eq!(parse_size("1.5 kb"), Some(1500));
+        assert_eq!(parse_size("512KiB"), Some(512 * 1024));
+        assert_eq!(parse_size("1x"), None);
+        assert_eq!(parse_size("-1"), None);
+        assert!(matches("size >= 1KiB", &file("a", 1024, "2020")));
+    }
+
+    #[test]
+    fn partial_times() {
+        assert_eq!(parse_time("2022"), parse_time("2022-01-01 00:00:00"));
+        assert_eq!(parse_time("2022-06"), parse_time("2022-06-01 00:00:00"));
+        assert_eq!(parse_time("2022-06-30"), parse_time("2022-06-30T00:00"));
+        assert_eq!(parse_time("2022-13"), None);
+        let item = file("a", 1, "2022-06-30 12:00:00");
+        assert!(matches("modified >= 2022-06-30 and modified < 2022-07", &item));
+        assert!(!matches("modified < 2022", &item));
+    }
+
+    #[test]
+    fn patterns() {
+        let item = file("Movie.MP4", 1, "2020");
+        assert!(!matches("name like \"movie*\"", &item));
+        assert!(matches("name like \"Mov?e.*\"", &item));
+        assert!(matches("ext like \"MP*\"", &item));
+        assert!(matches("ext like .mp[0-9]", &item));
+        assert!(matches("ext like \"mp[!3]\"", &item));
+        assert!(matches("md5 like \"D41D*\"", &item));
+        assert!(matches("meta.encoder matches \"^Lavf\"", &item));
+        assert!(!matches("meta.other matches \".*\"", &item));
+    }
+
+    #[test]
+    fn errors() {
+        let error = |source| Query::parse(source).err().unwrap();
+        assert_eq!(error("size >"), "Unexpected end of query");
+        assert_eq!(error("colour = red"), "Unknown field `colour`, expected name, ext, type, size, created, modified, accessed, md5 or meta.<key>");
+        assert_eq!(error("type < dir"), "`type` can only be compared with = and !=");
+        assert_eq!(error("type = pipe"), "Unknown type `pipe`, expected regular, dir or symlink");
+        assert_eq!(error("size > big"), "Invalid size `big`, expected something like 100, 2GB or 512KiB");
+        assert_eq!(error("(size > 1"), "Unexpected end of query");
+        assert_eq!(error("size > 1)"), "Unexpected `)`");
+        assert_eq!(error("size => 1"), "Unknown operator `=>`");
+        assert_eq!(error("name = \"a"), "Unclosed quote");
+    }
+}
@@ -4,32 +4,65 @@ use std::ops::RangeInclusive;
 
 #[derive(Serialize, Deserialize)]
 pub enum SpecificInfo {
-    Inaccessible(FileType),
+    Inaccessible(InaccessibleInfo),
     Regular(RegularInfo),
     Dir(DirInfo),
     Symlink(SymlinkInfo)
 }
 
+#[derive(Serialize, Deserialize)]
+pub struct InaccessibleInfo {
+    pub file_type: FileType,
+    // Unknown for records before version 5
+    pub reason: Option<String>
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct RegularInfo {
     pub md5: String,
-    pub metas: Vec<(String, String)>
+    // Unknown for records before version 3
+    pub size: Option<u64>,
+    // Device and inode number, which hard links share
+    pub inode: Option<(u64, u64)>,
+    pub metas: Vec<(String, String)>,
+    // What ffmpeg found or why it failed, when scanned with FFSM since version 6
+    pub media: Option<MediaInfo>,
+    pub probe_error: Option<String>
+}
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct MediaInfo {
+    // In microseconds
+    pub duration: Option<i64>,
+    pub video_codec: Option<String>,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub audio_codec: Option<String>
 }
 
 #[derive(Serialize, Deserialize)]
 pub struct DirInfo {
     pub childs: RangeInclusive<usize>,
+    // Entries were left out because the scan limit was reached
+    pub truncated: bool,
+    // Merkle hash of the contents, unknown for records before version 9
+    pub hash: Option<String>,
 }
 
 impl DirInfo {
-    pub fn new(start: usize, end: usize) -> Self {
+    pub fn new(start: usize, end: usize, truncated: bool) -> Self {
         Self {
-            childs: start..=end
+            childs: start..=end,
+            truncated,
+            hash: None
         }
     }
 }
 
 #[derive(Serialize, Deserialize)]
 pub struct SymlinkInfo {
-    pub target: Option<usize>
+    pub target: Option<usize>,
+    // The link as it is read and whether it points to nothing, unknown for records before version 5
+    pub link: Option<String>,
+    pub broken: Option<bool>
 }
\ No newline at end of file
@@ -1,23 +1,57 @@
 pub mod base;
 pub mod consts;
 
+mod audit_view;
+mod cleanup_view;
+mod diff_view;
+mod duplicates_view;
+mod hygiene_view;
+mod library_view;
+mod media_view;
+mod portability_view;
+mod query_view;
+mod report_view;
 mod scan_view;
+mod statistics_view;
+mod sunburst_view;
+mod timestamps_view;
+mod tree_view;
+mod treemap_view;
+mod verify_view;
 pub use scan_view::FileViewError;
 
 use base::*;
-use scan_view::scan_view;
-use crate::scan::{scan, Scan};
+use audit_view::audit_view;
+use cleanup_view::cleanup_view;
+use diff_view::diff_view;
+use duplicates_view::duplicates_view;
+use hygiene_view::hygiene_view;
+use library_view::library_view;
+use media_view::media_view;
+use portability_view::portability_view;
+use query_view::query_view;
+use scan_view::{scan_view, FileViewMode};
+use statistics_view::statistics_view;
+use timestamps_view::timestamps_view;
+use tree_view::{tree_id, TreeRow, TREE_ROW};
+use verify_view::verify_view;
+use crate::analysis::{self, AnomalyKind, CleanupKind, HygieneKind, LibraryReport, PortabilityKind, Severity};
+use crate::record::{self, Catalog, Compression, RecordError};
+use crate::scan::{scan, FileInfo, FileItem, NameIndex, Scan};
 use iced::{
-    Alignment, Length, Task,
-    widget::{button, column, container, horizontal_rule, horizontal_space, row, text, text_input},
+    Alignment, Length, Subscription, Task,
+    keyboard::{self, key::Named},
+    widget::{button, checkbox, column, container, horizontal_rule, horizontal_space, pick_list, row, scrollable, text, text_input},
 };
-use std::path::Path;
+use std::{collections::HashMap, path::Path};
+
+const REPORT_FILTERS: [(&str, &[&str]); 3] = [("Json", &["json"]), ("CSV", &["csv"]), ("TSV", &["tsv"])];
 
 pub fn setup() -> iced::Result {
     iced::application("File Info Scanner", update, view)
         .settings(iced::Settings {
             fonts: vec![include_bytes!("../assets/SourceHanSansSC-Regular.otf").into()],
-            default_font: iced::Font::with_name("Source Han Sans SC"),
+            default_font: consts::FONT,
             default_text_size: iced::Pixels(13.),
             ..Default::default()
         })
@@ -25,9 +59,20 @@ pub fn setup() -> iced::Result {
             size: iced::Size::new(600., 150.),
             ..iced::window::Settings::default()
         })
+        .subscription(subscription)
         .run()
 }
 
+fn subscription(_state: &State) -> Subscription<Message> {
+    iced::event::listen_with(|event, status, _| match event {
+        iced::Event::Window(iced::window::Event::FileDropped(path)) => Some(Message::Lookup(path.to_string_lossy().into_owned())),
+        // Arrows move through the tree unless a text input has them
+        iced::Event::Keyboard(keyboard::Event::KeyPressed { key: keyboard::Key::Named(key @ (Named::ArrowUp | Named::ArrowDown | Named::ArrowLeft | Named::ArrowRight)), .. })
+            if status == iced::event::Status::Ignored => Some(Message::TreeKey(key)),
+        _ => None,
+    })
+}
+
 fn view(state: &State) -> Element<'_> {
     let top = container(column![
         row![
@@ -39,6 +84,7 @@ fn view(state: &State) -> Element<'_> {
         row![
             text("Save to:").width(80),
             text_input("", &state.save_path).on_input(Message::SavePath),
+            pick_list(Compression::ALL, Some(state.save_compression), Message::SaveCompression),
             button("Choose").on_press(Message::SavePathFileDialog)
         ]
         .align_y(Alignment::Center),
@@ -48,21 +94,61 @@ fn view(state: &State) -> Element<'_> {
             button("Choose").on_press(Message::LoadPathFileDialog)
         ]
         .align_y(Alignment::Center),
+        row![text("Columns:").width(80)]
+            .extend(FileInfo::FIXED.into_iter().skip(1).map(|info| {
+                checkbox(info.title().to_owned(), state.file_view_infos.contains(&info)).on_toggle(move |_| Message::ToggleFileInfo(info.clone())).into()
+            }))
+            .push(text_input("Media keys, separated by commas", &state.media_keys).on_input(Message::MediaKeys))
+            .spacing(10)
+            .align_y(Alignment::Center),
     ])
     .align_top(Length::Shrink);
-    let scan_view = scan_view(state);
+    let tabs = row(Page::ALL.into_iter().map(|page| {
+        let selected = page == state.page;
+        button(page.title())
+            .style(move |theme, status| match selected {
+                true => button::primary(theme, status),
+                false => button::secondary(theme, status),
+            })
+            .on_press(Message::Page(page))
+            .into()
+    }))
+    .spacing(5);
+    // Errors are shown in the file view whatever the page is
+    let page = match state.page {
+        _ if state.file_view_error.is_some() => scan_view(state),
+        Page::Files => scan_view(state),
+        Page::Verify => verify_view(state),
+        Page::Diff => diff_view(state),
+        Page::Duplicates => duplicates_view(state),
+        Page::Library => library_view(state),
+        Page::Statistics => statistics_view(state),
+        Page::Media => media_view(state),
+        Page::Query => query_view(state),
+        Page::Hygiene => hygiene_view(state),
+        Page::Timestamps => timestamps_view(state),
+        Page::Portability => portability_view(state),
+        Page::Cleanup => cleanup_view(state),
+        Page::Audit => audit_view(state),
+    };
     let bottom = container(row![
         horizontal_space(),
         button("Scan").on_press(Message::ConfirmScan),
         button("Load").on_press(Message::ConfirmLoad),
+        button("Export table").on_press(Message::ExportTableFileDialog),
+        button("Export checksums").on_press(Message::ExportChecksumsFileDialog),
         button("Clear").on_press(Message::ClearFileView)
     ])
     .align_bottom(Length::Shrink);
-    column![top, horizontal_rule(2), scan_view, horizontal_rule(2), bottom].into()
+    column![top, horizontal_rule(2), tabs, page, horizontal_rule(2), bottom].into()
 }
 
 fn update(state: &mut State, message: Message) -> Task<Message> {
     match message {
+        Message::Page(page) => {
+            state.page = page;
+            Task::none()
+        }
         Message::ScanPath(path) => {
             state.scan_path = path;
             Task::none()
@@ -71,6 +157,10 @@ fn update(state: &mut State, message: Message) -> Task<Message> {
             state.save_path = path;
             Task::none()
         }
+        Message::SaveCompression(compression) => {
+            state.save_compression = compression;
+            Task::none()
+        }
         Message::LoadPath(path) => {
             state.load_path = path;
             Task::none()
@@ -83,18 +173,26 @@ fn update(state: &mut State, message: Message) -> Task<Message> {
             Some(handle) => Task::done(Message::SavePath(handle.path().to_str().unwrap().to_owned())),
             None => Task::none(),
         }),
-        Message::LoadPathFileDialog => Task::future(rfd::AsyncFileDialog::new().add_filter("Json", &["json"]).pick_file()).then(|handle| match handle {
+        Message::LoadPathFileDialog => Task::future(record_file_dialog().pick_file()).then(|handle| match handle {
             Some(handle) => Task::done(Message::LoadPath(handle.path().to_str().unwrap().to_owned())),
             None => Task::none(),
         }),
         Message::ConfirmScan => {
             clear_file_view(state);
             confirm_scan(state);
+            index_names(state);
+            index_twins(state);
+            index_usage(state);
+            index_tree(state);
             Task::none()
         }
         Message::ConfirmLoad => {
             clear_file_view(state);
             confirm_load(state);
+            index_names(state);
+            index_twins(state);
+            index_usage(state);
+            index_tree(state);
             Task::none()
         }
         Message::ClearFileView => {
@@ -103,6 +201,321 @@ fn update(state: &mut State, message: Message) -> Task<Message> {
         }
         Message::FileViewCurrent(id) => {
             state.file_view_current = id;
+            catalog_view_current(state, id);
+            reveal_tree(state)
+        }
+        Message::TreeToggle(id) => {
+            if let Some(row) = state.tree.iter().position(|row| row.id == id) {
+                toggle_tree(state, row);
+            }
+            Task::none()
+        }
+        Message::TreeKey(key) => match state.page {
+            Page::Files => tree_key(state, key),
+            _ => Task::none(),
+        },
+        Message::TreeScroll(offset, height) => {
+            state.tree_offset = offset;
+            state.tree_height = height;
+            Task::none()
+        }
+        Message::FileViewMode(mode) => {
+            state.file_view_mode = mode;
+            index_usage(state);
+            Task::none()
+        }
+        Message::TreemapColor(color) => {
+            state.treemap_color = color;
+            Task::none()
+        }
+        Message::SunburstMeasure(measure) => {
+            state.sunburst_measure = measure;
+            Task::none()
+        }
+        Message::NameQuery(query) => {
+            state.name_query = query;
+            search_names(state);
+            Task::none()
+        }
+        Message::NameSearch(search) => {
+            state.name_search = search;
+            search_names(state);
+            Task::none()
+        }
+        Message::NameMatchCase(match_case) => {
+            state.name_match_case = match_case;
+            search_names(state);
+            Task::none()
+        }
+        Message::ToggleFileInfo(info) => {
+            update_file_view_infos(state, Some(info));
+            Task::none()
+        }
+        Message::MediaKeys(keys) => {
+            state.media_keys = keys;
+            update_file_view_infos(state, None);
+            Task::none()
+        }
+        Message::ExportTableFileDialog => save_file_dialog(&[("CSV", &["csv"]), ("TSV", &["tsv"])], Message::ExportTable),
+        Message::ExportTable(path) => {
+            let infos = state.file_view_infos.clone();
+            export_scan(state, |scan| record::export_table(scan, Path::new(&path), &infos));
+            Task::none()
+        }
+        Message::ExportChecksumsFileDialog => save_file_dialog(&[("MD5", &["md5"])], Message::ExportChecksums),
+        Message::ExportChecksums(path) => {
+            export_scan(state, |scan| record::export_checksums(scan, Path::new(&path)));
+            Task::none()
+        }
+        Message::Reveal(id) => {
+            let item = match (&state.scan, &state.catalog) {
+                (Some(scan), _) => Ok(Some(containing_dir(&scan.items[id]))),
+                (None, Some(view)) => view.catalog.item(view.scan.id, id).map(|item| Some(containing_dir(&item))),
+                (None, None) => Ok(None),
+            };
+            match item {
+                Err(err) => state.file_view_error = Some(err.into()),
+                Ok(None) => (),
+                Ok(Some(dir)) => {
+                    state.page = Page::Files;
+                    state.file_view_current = dir;
+                    state.name_query.clear();
+                    state.name_matches.clear();
+                    catalog_view_current(state, dir);
+                    return reveal_tree(state);
+                }
+            }
+            Task::none()
+        }
+        Message::VerifyRoot(root) => {
+            state.verify_root = root;
+            Task::none()
+        }
+        Message::ConfirmVerify => {
+            let root = state.verify_root.clone();
+            match with_scan(state, |scan| analysis::verify(scan, Path::new(&root))) {
+                Err(err) => state.file_view_error = Some(err.into()),
+                Ok(report) => state.verify_report = report,
+            }
+            Task::none()
+        }
+        Message::ExportVerifyFileDialog => save_file_dialog(&[("Json", &["json"])], Message::ExportVerify),
+        Message::ExportVerify(path) => {
+            if let Some(report) = &state.verify_report {
+                if let Err(err) = record::export_json(report, Path::new(&path)) {
+                    state.file_view_error = Some(err.into());
+                }
+            }
+            Task::none()
+        }
+        Message::DiffPath(path) => {
+            state.diff_path = path;
+            Task::none()
+        }
+        Message::DiffPathFileDialog => Task::future(record_file_dialog().pick_file()).then(|handle| match handle {
+            Some(handle) => Task::done(Message::DiffPath(handle.path().to_str().unwrap().to_owned())),
+            None => Task::none(),
+        }),
+        Message::ConfirmDiff => {
+            let diff = match state.diff_content_only {
+                true => analysis::content_diff,
+                false => analysis::diff,
+            };
+            let result = record::load(Path::new(&state.diff_path)).and_then(|old| with_scan(state, |new| diff(&old, new)));
+            match result {
+                Err(err) => state.file_view_error = Some(err.into()),
+                Ok(report) => {
+                    state.diff_report = report;
+                    state.diff_filter = None;
+                }
+            }
+            Task::none()
+        }
+        Message::DiffContentOnly(content_only) => {
+            state.diff_content_only = content_only;
+            Task::none()
+        }
+        Message::DiffFilter(path) => {
+            state.diff_filter = Some(path);
+            Task::none()
+        }
+        Message::ExportDiffFileDialog => save_file_dialog(&[("Json", &["json"])], Message::ExportDiff),
+        Message::ExportDiff(path) => {
+            if let Some(report) = &state.diff_report {
+                if let Err(err) = record::export_json(report, Path::new(&path)) {
+                    state.file_view_error = Some(err.into());
+                }
+            }
+            Task::none()
+        }
+        Message::ConfirmDuplicates => {
+            match with_scan(state, analysis::duplicates) {
+                Err(err) => state.file_view_error = Some(err.into()),
+                Ok(report) => state.duplicate_report = report,
+            }
+            Task::none()
+        }
+        Message::ExportDuplicatesFileDialog => save_file_dialog(&REPORT_FILTERS, Message::ExportDuplicates),
+        Message::ExportDuplicates(path) => {
+            if let Some(Err(err)) = state.duplicate_report.as_ref().map(|report| record::export_report(report, Path::new(&path))) {
+                state.file_view_error = Some(err.into());
+            }
+            Task::none()
+        }
+        Message::LibraryAddFileDialog => Task::future(record_file_dialog().pick_files()).then(|handles| match handles {
+            Some(handles) => Task::done(Message::LibraryAdd(handles.iter().map(|handle| handle.path().to_str().unwrap().to_owned()).collect())),
+            None => Task::none(),
+        }),
+        Message::LibraryAdd(paths) => {
+            for path in paths {
+                if let Err(err) = state.library.add(Path::new(&path)) {
+                    state.file_view_error = Some(err.into());
+                    break;
+                }
+            }
+            Task::none()
+        }
+        Message::LibraryClear => {
+            state.library = Default::default();
+            state.library_report = None;
+            state.lookup_report = None;
+            Task::none()
+        }
+        Message::LibraryQuery(query) => {
+            state.library_query = query;
+            Task::none()
+        }
+        Message::ConfirmLibraryQuery => {
+            let group = state.library.locations(state.library_query.trim());
+            state.library_report = Some(LibraryReport { groups: group.into_iter().collect() });
+            state.lookup_report = None;
+            Task::none()
+        }
+        Message::ConfirmLibraryDuplicates => {
+            state.library_report = Some(state.library.duplicates());
+            state.lookup_report = None;
+            Task::none()
+        }
+        Message::ExportLibraryFileDialog => save_file_dialog(&REPORT_FILTERS, Message::ExportLibrary),
+        Message::ExportLibrary(path) => {
+            if let Some(Err(err)) = state.library_report.as_ref().map(|report| record::export_report(report, Path::new(&path))) {
+                state.file_view_error = Some(err.into());
+            }
+            Task::none()
+        }
+        Message::LookupFileDialog => Task::future(rfd::AsyncFileDialog::new().pick_file()).then(|handle| match handle {
+            Some(handle) => Task::done(Message::Lookup(handle.path().to_str().unwrap().to_owned())),
+            None => Task::none(),
+        }),
+        Message::Lookup(path) => {
+            state.page = Page::Library;
+            match analysis::lookup(&state.library, Path::new(&path)) {
+                Ok(report) => {
+                    state.lookup_report = Some(report);
+                    state.library_report = None;
+                }
+                Err(err) => state.file_view_error = Some(RecordError::from(err).into()),
+            }
+            Task::none()
+        }
+        Message::ExportLookupFileDialog => save_file_dialog(&REPORT_FILTERS, Message::ExportLookup),
+        Message::ExportLookup(path) => {
+            if let Some(Err(err)) = state.lookup_report.as_ref().map(|report| record::export_report(report, Path::new(&path))) {
+                state.file_view_error = Some(err.into());
+            }
+            Task::none()
+        }
+        Message::ConfirmStatistics => {
+            match with_scan(state, analysis::statistics) {
+                Err(err) => state.file_view_error = Some(err.into()),
+                Ok(statistics) => state.statistics = statistics,
+            }
+            Task::none()
+        }
+        Message::ExportStatisticsFileDialog => save_file_dialog(&[("Json", &["json"])], Message::ExportStatistics),
+        Message::ExportStatistics(path) => {
+            if let Some(Err(err)) = state.statistics.as_ref().map(|statistics| record::export_json(statistics, Path::new(&path))) {
+                state.file_view_error = Some(err.into());
+            }
+            Task::none()
+        }
+        Message::ConfirmMedia => {
+            match with_scan(state, analysis::media) {
+                Err(err) => state.file_view_error = Some(err.into()),
+                Ok(report) => state.media_report = report,
+            }
+            Task::none()
+        }
+        Message::ExportMediaFileDialog => save_file_dialog(&[("Json", &["json"])], Message::ExportMedia),
+        Message::ExportMedia(path) => {
+            if let Some(Err(err)) = state.media_report.as_ref().map(|report| record::export_json(report, Path::new(&path))) {
+                state.file_view_error = Some(err.into());
+            }
+            Task::none()
+        }
+        Message::Query(query) => {
+            state.query = query;
+            Task::none()
+        }
+        Message::ConfirmQuery => {
+            let source = state.query.clone();
+            match with_scan(state, |scan| analysis::query(scan, &source)) {
+                Err(err) => state.file_view_error = Some(err.into()),
+                Ok(None) => (),
+                Ok(Some(Err(err))) => {
+                    state.query_report = None;
+                    state.query_error = Some(err);
+                }
+                Ok(Some(Ok(report))) => {
+                    state.query_report = Some(report);
+                    state.query_error = None;
+                }
+            }
+            Task::none()
+        }
+        Message::ExportQueryFileDialog => save_file_dialog(&REPORT_FILTERS, Message::ExportQuery),
+        Message::ExportQuery(path) => {
+            if let Some(Err(err)) = state.query_report.as_ref().map(|report| record::export_report(report, Path::new(&path))) {
+                state.file_view_error = Some(err.into());
+            }
+            Task::none()
+        }
+        Message::ConfirmReport(report) => {
+            if let Err(err) = confirm_report(state, report) {
+                state.file_view_error = Some(err.into());
+            }
+            Task::none()
+        }
+        Message::ReportFilter(report, filter) => {
+            match report {
+                Report::Hygiene => state.hygiene_filter = filter.map(|i| HygieneKind::ALL[i]),
+                Report::Timestamps => state.timestamp_filter = filter.map(|i| AnomalyKind::ALL[i]),
+                Report::Portability => state.portability_filter = filter.map(|i| PortabilityKind::ALL[i]),
+                Report::Cleanup => state.cleanup_filter = filter.map(|i| CleanupKind::ALL[i]),
+                Report::Audit => state.audit_filter = filter.map(|i| Severity::ALL[i]),
+            }
+            Task::none()
+        }
+        Message::ExportReportFileDialog(report) => save_file_dialog(&REPORT_FILTERS, move |path| Message::ExportReport(report, path)),
+        Message::ExportReport(report, path) => {
+            if let Err(err) = export_report(state, report, Path::new(&path)) {
+                state.file_view_error = Some(err.into());
+            }
+            Task::none()
+        }
+        Message::CatalogScan(scan) => {
+            if let Some(view) = state.catalog.as_mut() {
+                view.scan = scan;
+            }
+            // Reports, flags and sizes are by the ids of the former scan
+            clear_reports(state);
+            state.scan_generation += 1;
+            state.file_view_current = 0;
+            catalog_view_current(state, 0);
+            index_names(state);
+            index_twins(state);
+            index_usage(state);
+            index_tree(state);
             Task::none()
         }
     }
@@ -110,10 +523,33 @@ fn update(state: &mut State, message: Message) -> Task<Message> {
 
 fn clear_file_view(state: &mut State) {
     state.scan = None;
+    state.catalog = None;
+    clear_reports(state);
+    state.scan_generation += 1;
+    state.name_index = None;
+    state.name_matches.clear();
+    state.dir_twins.clear();
+    state.tree.clear();
     state.file_view_error = None;
     state.file_view_current = 0;
 }
 
+// Everything computed from the scan, as it refers to items by their ids
+fn clear_reports(state: &mut State) {
+    state.verify_report = None;
+    state.diff_report = None;
+    state.duplicate_report = None;
+    state.statistics = None;
+    state.media_report = None;
+    state.query_report = None;
+    state.hygiene_report = None;
+    state.timestamp_report = None;
+    state.portability_report = None;
+    state.cleanup_report = None;
+    state.audit_report = None;
+    state.disk_usage = None;
+}
+
 fn confirm_scan(state: &mut State) {
     let scan_path = Path::new(&state.scan_path);
     let save_path = Path::new(&state.save_path);
@@ -121,9 +557,12 @@ fn confirm_scan(state: &mut State) {
         false => state.file_view_error = Some(FileViewError::InvalidScanPath),
         true => match scan(&scan_path, &state.scan_settings) {
             Err(err) => state.file_view_error = Some(FileViewError::FileIoError(err)),
-            Ok(scan) => match std::fs::write(save_path, serde_json::to_string(&scan).unwrap()) {
-                Err(err) => state.file_view_error = Some(FileViewError::FileIoError(err.to_string())),
-                Ok(_) => state.scan = Some(scan),
+            Ok(scan) => match record::save(&scan, save_path, state.save_compression) {
+                Err(err) => state.file_view_error = Some(err.into()),
+                Ok(_) => {
+                    state.verify_root = scan.root.clone();
+                    state.scan = Some(scan);
+                }
             }
         },
     }
@@ -133,15 +572,257 @@ fn confirm_load(state: &mut State) {
     let load_path = Path::new(&state.load_path);
     match load_path.is_file() {
         false => state.file_view_error = Some(FileViewError::InvalidLoadPath),
-        true => match std::fs::read(load_path) {
-            Err(err) => state.file_view_error = Some(FileViewError::FileIoError(err.to_string())),
-            Ok(content) => match String::from_utf8(content) {
-                Err(_) => state.file_view_error = Some(FileViewError::InvalidLoadContent),
-                Ok(string) => match serde_json::from_str::<Scan>(&string) {
-                    Err(_) => state.file_view_error = Some(FileViewError::InvalidLoadContent),
-                    Ok(scan) => state.scan = Some(scan),
-                },
-            },
+        true if record::is_catalog(load_path) => match open_catalog(load_path) {
+            Err(err) => state.file_view_error = Some(err.into()),
+            Ok(view) => {
+                state.verify_root = view.scan.root.clone();
+                state.catalog = Some(view);
+            }
+        },
+        true => match record::load(load_path) {
+            Err(err) => state.file_view_error = Some(err.into()),
+            Ok(scan) => {
+                state.verify_root = scan.root.clone();
+                state.scan = Some(scan);
+            }
         },
     }
-}
\ No newline at end of file
+}
+
+fn open_catalog(path: &Path) -> Result<CatalogView, RecordError> {
+    let catalog = Catalog::open(path)?;
+    let scans = catalog.scans()?;
+    let scan = scans.last().cloned().ok_or(RecordError::InvalidContent)?;
+    let current = catalog.item(scan.id, 0)?;
+    let childs = catalog.childs(scan.id, &current)?;
+    Ok(CatalogView { catalog, scans, scan, current, childs })
+}
+
+fn catalog_view_current(state: &mut State, id: usize) {
+    let Some(view) = state.catalog.as_mut() else {
+        return;
+    };
+    let result = view.catalog.item(view.scan.id, id).and_then(|current| Ok((view.catalog.childs(view.scan.id, &current)?, current)));
+    match result {
+        Err(err) => state.file_view_error = Some(err.into()),
+        Ok((childs, current)) => {
+            view.current = current;
+            view.childs = childs;
+        }
+    }
+}
+
+// Names are indexed on load, so searching them is fast enough to do as you type
+fn index_names(state: &mut State) {
+    let names = match (&state.scan, &state.catalog) {
+        (Some(scan), _) => Ok(scan.items.iter().map(|item| (item.name().to_owned(), item.parent())).collect()),
+        (None, Some(view)) => view.catalog.names(view.scan.id),
+        (None, None) => return,
+    };
+    match names {
+        Err(err) => state.file_view_error = Some(err.into()),
+        Ok(names) => state.name_index = Some(NameIndex::new(names)),
+    }
+    search_names(state);
+}
+
+// Directories with the same contents by their Merkle hash, empty ones are all the same
+fn index_twins(state: &mut State) {
+    let hashes = match (&state.scan, &state.catalog) {
+        (Some(scan), _) => Ok(scan.items.iter().filter_map(|item| item.dir_hash().map(|hash| (item.id(), hash.to_owned()))).collect()),
+        (None, Some(view)) => view.catalog.dir_hashes(view.scan.id),
+        (None, None) => return,
+    };
+    let mut twins: HashMap<String, Vec<usize>> = HashMap::new();
+    match hashes {
+        Err(err) => state.file_view_error = Some(err.into()),
+        Ok(hashes) => for (id, hash) in hashes.into_iter().filter(|(_, hash)| hash != analysis::EMPTY_MD5) {
+            twins.entry(hash).or_default().push(id);
+        }
+    }
+    twins.retain(|_, ids| ids.len() > 1);
+    state.dir_twins = twins;
+}
+
+// A catalog scan is loaded as a whole for the sizes, so they wait until a chart is shown
+fn index_usage(state: &mut State) {
+    if state.file_view_mode == FileViewMode::List || state.disk_usage.is_some() {
+        return;
+    }
+    match with_scan(state, analysis::disk_usage) {
+        Err(err) => state.file_view_error = Some(err.into()),
+        Ok(usage) => state.disk_usage = usage,
+    }
+}
+
+// The tree starts with the root expanded, other directories are read once they are expanded
+fn index_tree(state: &mut State) {
+    state.tree.clear();
+    if state.scan.is_none() && state.catalog.is_none() {
+        return;
+    }
+    state.tree.push(TreeRow { id: 0, name: ".".to_owned(), depth: 0, expandable: true, expanded: false });
+    toggle_tree(state, 0);
+}
+
+// Accessible directories inside a directory by name, with whether they have anything inside
+fn child_dirs(state: &State, id: usize) -> Result<Vec<(usize, String, bool)>, RecordError> {
+    match (&state.scan, &state.catalog) {
+        (Some(scan), _) => {
+            let range = scan.items[id].childs().unwrap_or(1..=0);
+            let mut dirs: Vec<_> = scan.items[range].iter()
+                .filter_map(|item| item.childs().map(|childs| (item.id(), item.name().to_owned(), !childs.is_empty())))
+                .collect();
+            dirs.sort_by(|(_, namea, _), (_, nameb, _)| namea.cmp(nameb));
+            Ok(dirs)
+        }
+        (None, Some(view)) => view.catalog.child_dirs(view.scan.id, id),
+        (None, None) => Ok(Vec::new()),
+    }
+}
+
+// Only the rows below the toggled one change, so a directory with many children is expanded at once
+fn toggle_tree(state: &mut State, row: usize) {
+    let (id, depth, expanded) = (state.tree[row].id, state.tree[row].depth, state.tree[row].expanded);
+    if expanded {
+        let end = state.tree[row + 1..].iter().position(|child| child.depth <= depth).map_or(state.tree.len(), |end| row + 1 + end);
+        state.tree.drain(row + 1..end);
+    } else {
+        match child_dirs(state, id) {
+            Err(err) => {
+                state.file_view_error = Some(err.into());
+                return;
+            }
+            Ok(dirs) => {
+                let childs = dirs.into_iter().map(|(id, name, expandable)| TreeRow { id, name, depth: depth + 1, expandable, expanded: false });
+                state.tree.splice(row + 1..row + 1, childs);
+            }
+        }
+    }
+    state.tree[row].expanded = !expanded;
+}
+
+// Expands the ancestors of the current directory and scrolls to it if it is out of sight
+fn reveal_tree(state: &mut State) -> Task<Message> {
+    let Some(index) = &state.name_index else {
+        return Task::none();
+    };
+    let mut ancestors = Vec::new();
+    let mut id = state.file_view_current;
+    while let Some(parent) = index.parent(id) {
+        ancestors.push(parent);
+        id = parent;
+    }
+    for ancestor in ancestors.into_iter().rev() {
+        match state.tree.iter().position(|row| row.id == ancestor) {
+            Some(row) if !state.tree[row].expanded => toggle_tree(state, row),
+            Some(_) => (),
+            None => return Task::none(),
+        }
+    }
+    let Some(row) = state.tree.iter().position(|row| row.id == state.file_view_current) else {
+        return Task::none();
+    };
+    let top = row as f32 * TREE_ROW;
+    // Rows below are only known to be out of sight once the height is
+    let offset = match top < state.tree_offset {
+        true => top,
+        false if state.tree_height > 0. && top + TREE_ROW > state.tree_offset + state.tree_height => top + TREE_ROW - state.tree_height,
+        false => return Task::none(),
+    };
+    scrollable::scroll_to(tree_id(), scrollable::AbsoluteOffset { x: 0., y: offset.max(0.) })
+}
+
+// Up and down go through the rows, right expands or goes in and left collapses or goes out
+fn tree_key(state: &mut State, key: Named) -> Task<Message> {
+    let Some(row) = state.tree.iter().position(|row| row.id == state.file_view_current) else {
+        return Task::none();
+    };
+    let current = &state.tree[row];
+    let target = match key {
+        Named::ArrowUp => row.checked_sub(1),
+        Named::ArrowDown => (row + 1 < state.tree.len()).then_some(row + 1),
+        Named::ArrowRight | Named::ArrowLeft if current.expandable && current.expanded == (key == Named::ArrowLeft) => {
+            toggle_tree(state, row);
+            None
+        }
+        Named::ArrowRight => state.tree.get(row + 1).filter(|child| child.depth > current.depth).map(|_| row + 1),
+        Named::ArrowLeft => state.tree[..row].iter().rposition(|parent| parent.depth < current.depth),
+        _ => None,
+    };
+    match target {
+        Some(target) => Task::done(Message::FileViewCurrent(state.tree[target].id)),
+        None => Task::none(),
+    }
+}
+
+fn search_names(state: &mut State) {
+    state.name_matches = match &state.name_index {
+        Some(index) => index.search(&state.name_query, state.name_search, state.name_match_case, consts::REPORT_ROWS),
+        None => Vec::new(),
+    };
+}
+
+fn update_file_view_infos(state: &mut State, toggled: Option<FileInfo>) {
+    // `Name` is never toggled, the first column also holds the `..` entry
+    let fixed = FileInfo::FIXED.into_iter().filter(|info| state.file_view_infos.contains(info) != (Some(info) == toggled.as_ref()));
+    let media = state.media_keys.split(',').map(str::trim).filter(|key| !key.is_empty()).map(|key| FileInfo::Media(key.to_owned()));
+    state.file_view_infos = fixed.chain(media).collect();
+}
+
+fn confirm_report(state: &mut State, report: Report) -> Result<(), RecordError> {
+    match report {
+        Report::Hygiene => state.hygiene_report = with_scan(state, analysis::hygiene)?,
+        Report::Timestamps => state.timestamp_report = with_scan(state, analysis::timestamps)?,
+        Report::Portability => state.portability_report = with_scan(state, analysis::portability)?,
+        Report::Cleanup => state.cleanup_report = with_scan(state, analysis::cleanup)?,
+        Report::Audit => state.audit_report = with_scan(state, analysis::audit)?,
+    }
+    Ok(())
+}
+
+// Nothing is written before the report is computed
+fn export_report(state: &State, report: Report, path: &Path) -> Result<(), RecordError> {
+    let exported = match report {
+        Report::Hygiene => state.hygiene_report.as_ref().map(|report| record::export_report(report, path)),
+        Report::Timestamps => state.timestamp_report.as_ref().map(|report| record::export_report(report, path)),
+        Report::Portability => state.portability_report.as_ref().map(|report| record::export_report(report, path)),
+        Report::Cleanup => state.cleanup_report.as_ref().map(|report| record::export_report(report, path)),
+        Report::Audit => state.audit_report.as_ref().map(|report| record::export_report(report, path)),
+    };
+    exported.unwrap_or(Ok(()))
+}
+
+// A catalog only has the current directory in memory, so the whole scan is loaded for this
+fn with_scan<T>(state: &State, f: impl FnOnce(&Scan) -> T) -> Result<Option<T>, RecordError> {
+    match (&state.scan, &state.catalog) {
+        (Some(scan), _) => Ok(Some(f(scan))),
+        (None, Some(view)) => view.catalog.load(view.scan.id).map(|scan| Some(f(&scan))),
+        (None, None) => Ok(None),
+    }
+}
+
+fn export_scan(state: &mut State, export: impl FnOnce(&Scan) -> Result<(), RecordError>) {
+    if let Err(err) = with_scan(state, export).and_then(|result| result.unwrap_or(Ok(()))) {
+        state.file_view_error = Some(err.into());
+    }
+}
+
+fn containing_dir(item: &FileItem) -> usize {
+    match item.is_dir() {
+        true => item.id(),
+        false => item.parent().unwrap_or(0),
+    }
+}
+
+fn record_file_dialog() -> rfd::AsyncFileDialog {
+    rfd::AsyncFileDialog::new().add_filter("Scan record", &["sixx", "json", "ndjson", "jsonl", "zst", "gz", "db", "sqlite", "md5"])
+}
+
+fn save_file_dialog(filters: &[(&str, &[&str])], message: impl Fn(String) -> Message + Send + 'static) -> Task<Message> {
+    let dialog = filters.iter().fold(rfd::AsyncFileDialog::new(), |dialog, (name, extensions)| dialog.add_filter(*name, *extensions));
+    Task::future(dialog.save_file()).then(move |handle| match handle {
+        Some(handle) => Task::done(message(handle.path().to_str().unwrap().to_owned())),
+        None => Task::none(),
+    })
+}
@@ -0,0 +1,37 @@
+use super::base::*;
+use super::report_view::{cell, item_link, report_filters, report_footer, report_table};
+use crate::analysis::Severity;
+use iced::widget::{button, column, horizontal_space, row, text};
+use iced::{Alignment, Length};
+
+pub fn audit_view(state: &State) -> Element<'_> {
+    let top = row![
+        text("World-writable items, setuid and setgid files, unknown owners, group-writable homes and world-writable directories without the sticky bit"),
+        horizontal_space(),
+        button("Audit").on_press(Message::ConfirmReport(Report::Audit))
+    ]
+    .align_y(Alignment::Center);
+    let Some(report) = &state.audit_report else {
+        return column![top, Container::new(text("Findings will be listed here by severity")).center(Length::Fill)].into();
+    };
+    let severities = report_filters(Report::Audit, &Severity::ALL, state.audit_filter, |severity| format!("{}: {}", severity.title(), report.count(severity)));
+    let entries: Vec<_> = report.entries.iter().filter(|entry| state.audit_filter.is_none_or(|severity| entry.severity == severity)).collect();
+    let rows = entries.iter().map(|entry| vec![
+        cell(entry.severity.title()),
+        cell(entry.kind.title()),
+        item_link(entry.path.clone(), entry.id),
+        cell(&entry.mode),
+        cell(&entry.detail),
+    ]);
+    let summary = match report.unknown {
+        0 => format!("{} findings", report.entries.len()),
+        unknown => format!("{} findings, {} items without permissions, which records before version 8 do not have", report.entries.len(), unknown),
+    };
+    column![
+        top,
+        severities,
+        report_table(&["Severity", "Kind", "Path", "Mode", "Detail"], rows),
+        report_footer(summary, Report::Audit)
+    ]
+    .into()
+}
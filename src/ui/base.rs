@@ -1,37 +1,196 @@
 use super::FileViewError;
-use crate::scan::{FileInfo, Scan, ScanSettings};
+use super::scan_view::FileViewMode;
+use super::sunburst_view::SunburstMeasure;
+use super::tree_view::TreeRow;
+use super::treemap_view::TreemapColor;
+use crate::analysis::{AnomalyKind, AnomalyReport, AuditReport, CleanupKind, CleanupReport, DiffReport, DiskUsage, DuplicateReport, HygieneKind, HygieneReport, Library, LibraryReport, LookupReport, MediaReport, PortabilityKind, PortabilityReport, QueryReport, Severity, Statistics, VerifyReport};
+use crate::record::{Catalog, CatalogScan, Compression};
+use crate::scan::{FileInfo, FileItem, NameIndex, NameSearch, Scan, ScanSettings};
+use iced::keyboard::key::Named;
+use std::collections::HashMap;
 
 pub struct State {
+    pub page: Page,
     pub scan_path: String,
     pub save_path: String,
+    pub save_compression: Compression,
     pub load_path: String,
     pub scan_settings: ScanSettings,
     pub scan: Option<Scan>,
+    pub catalog: Option<CatalogView>,
     pub file_view_error: Option<FileViewError>,
     pub file_view_current: usize,
-    pub file_view_infos: Vec<FileInfo>
+    pub file_view_infos: Vec<FileInfo>,
+    pub file_view_mode: FileViewMode,
+    pub tree: Vec<TreeRow>,
+    // The scrolled offset and the height of the tree, zero until it is first drawn
+    pub tree_offset: f32,
+    pub tree_height: f32,
+    pub treemap_color: TreemapColor,
+    pub sunburst_measure: SunburstMeasure,
+    // Recursive sizes, only computed once a chart of them is shown
+    pub disk_usage: Option<DiskUsage>,
+    // Bumped whenever the scan is replaced, as the charts keep their layouts from one scan to the next
+    pub scan_generation: u64,
+    pub media_keys: String,
+    pub name_index: Option<NameIndex>,
+    // Merkle hashes shared by more than one directory
+    pub dir_twins: HashMap<String, Vec<usize>>,
+    pub name_query: String,
+    pub name_search: NameSearch,
+    pub name_match_case: bool,
+    pub name_matches: Vec<usize>,
+    pub verify_root: String,
+    pub verify_report: Option<VerifyReport>,
+    pub diff_path: String,
+    pub diff_content_only: bool,
+    pub diff_report: Option<DiffReport>,
+    pub diff_filter: Option<String>,
+    pub duplicate_report: Option<DuplicateReport>,
+    pub library: Library,
+    pub library_query: String,
+    pub library_report: Option<LibraryReport>,
+    pub lookup_report: Option<LookupReport>,
+    pub statistics: Option<Statistics>,
+    pub media_report: Option<MediaReport>,
+    pub query: String,
+    pub query_report: Option<QueryReport>,
+    pub query_error: Option<String>,
+    pub hygiene_report: Option<HygieneReport>,
+    pub hygiene_filter: Option<HygieneKind>,
+    pub timestamp_report: Option<AnomalyReport>,
+    pub timestamp_filter: Option<AnomalyKind>,
+    pub portability_report: Option<PortabilityReport>,
+    pub portability_filter: Option<PortabilityKind>,
+    pub cleanup_report: Option<CleanupReport>,
+    pub cleanup_filter: Option<CleanupKind>,
+    pub audit_report: Option<AuditReport>,
+    pub audit_filter: Option<Severity>
 }
 
 impl Default for State {
     fn default() -> Self {
         let file_view_infos = vec![FileInfo::Name, FileInfo::Created, FileInfo::Modified, FileInfo::Accessed];
         Self {
+            page: Page::Files,
             scan_path: String::new(),
             save_path: String::new(),
+            save_compression: Compression::Auto,
             load_path: String::new(),
             scan_settings: Default::default(),
             scan: None,
+            catalog: None,
             file_view_error: None,
             file_view_current: 0,
-            file_view_infos
+            file_view_infos,
+            file_view_mode: FileViewMode::List,
+            tree: Vec::new(),
+            tree_offset: 0.,
+            tree_height: 0.,
+            treemap_color: TreemapColor::Category,
+            sunburst_measure: SunburstMeasure::Size,
+            disk_usage: None,
+            scan_generation: 0,
+            media_keys: String::new(),
+            name_index: None,
+            dir_twins: HashMap::new(),
+            name_query: String::new(),
+            name_search: NameSearch::Prefix,
+            name_match_case: false,
+            name_matches: Vec::new(),
+            verify_root: String::new(),
+            verify_report: None,
+            diff_path: String::new(),
+            diff_content_only: false,
+            diff_report: None,
+            diff_filter: None,
+            duplicate_report: None,
+            library: Library::default(),
+            library_query: String::new(),
+            library_report: None,
+            lookup_report: None,
+            statistics: None,
+            media_report: None,
+            query: String::new(),
+            query_report: None,
+            query_error: None,
+            hygiene_report: None,
+            hygiene_filter: None,
+            timestamp_report: None,
+            timestamp_filter: None,
+            portability_report: None,
+            portability_filter: None,
+            cleanup_report: None,
+            cleanup_filter: None,
+            audit_report: None,
+            audit_filter: None
         }
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Page {
+    Files,
+    Verify,
+    Diff,
+    Duplicates,
+    Library,
+    Statistics,
+    Media,
+    Query,
+    Hygiene,
+    Timestamps,
+    Portability,
+    Cleanup,
+    Audit,
+}
+
+impl Page {
+    pub const ALL: [Page; 13] = [Page::Files, Page::Verify, Page::Diff, Page::Duplicates, Page::Library, Page::Statistics, Page::Media, Page::Query, Page::Hygiene, Page::Timestamps, Page::Portability, Page::Cleanup, Page::Audit];
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            Page::Files => "Files",
+            Page::Verify => "Verify",
+            Page::Diff => "Diff",
+            Page::Duplicates => "Duplicates",
+            Page::Library => "Library",
+            Page::Statistics => "Statistics",
+            Page::Media => "Media",
+            Page::Query => "Query",
+            Page::Hygiene => "Hygiene",
+            Page::Timestamps => "Timestamps",
+            Page::Portability => "Portability",
+            Page::Cleanup => "Cleanup",
+            Page::Audit => "Audit",
+        }
+    }
+}
+
+// Reports listing items by kind, with a filter for each kind
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Report {
+    Hygiene,
+    Timestamps,
+    Portability,
+    Cleanup,
+    Audit,
+}
+
+pub struct CatalogView {
+    pub catalog: Catalog,
+    pub scans: Vec<CatalogScan>,
+    pub scan: CatalogScan,
+    pub current: FileItem,
+    pub childs: Vec<FileItem>
+}
+
 #[derive(Debug, Clone)]
 pub enum Message {
+    Page(Page),
     ScanPath(String),
     SavePath(String),
+    SaveCompression(Compression),
     LoadPath(String),
     ScanPathFileDialog,
     SavePathFileDialog,
@@ -39,7 +198,65 @@ pub enum Message {
     ConfirmScan,
     ConfirmLoad,
     ClearFileView,
-    FileViewCurrent(usize)
+    FileViewCurrent(usize),
+    FileViewMode(FileViewMode),
+    TreeToggle(usize),
+    TreeKey(Named),
+    TreeScroll(f32, f32),
+    TreemapColor(TreemapColor),
+    SunburstMeasure(SunburstMeasure),
+    CatalogScan(CatalogScan),
+    ToggleFileInfo(FileInfo),
+    MediaKeys(String),
+    NameQuery(String),
+    NameSearch(NameSearch),
+    NameMatchCase(bool),
+    ExportTableFileDialog,
+    ExportTable(String),
+    ExportChecksumsFileDialog,
+    ExportChecksums(String),
+    Reveal(usize),
+    VerifyRoot(String),
+    ConfirmVerify,
+    ExportVerifyFileDialog,
+    ExportVerify(String),
+    DiffPath(String),
+    DiffPathFileDialog,
+    ConfirmDiff,
+    DiffContentOnly(bool),
+    DiffFilter(String),
+    ExportDiffFileDialog,
+    ExportDiff(String),
+    ConfirmDuplicates,
+    ExportDuplicatesFileDialog,
+    ExportDuplicates(String),
+    LibraryAddFileDialog,
+    LibraryAdd(Vec<String>),
+    LibraryClear,
+    LibraryQuery(String),
+    ConfirmLibraryQuery,
+    ConfirmLibraryDuplicates,
+    ExportLibraryFileDialog,
+    ExportLibrary(String),
+    LookupFileDialog,
+    Lookup(String),
+    ExportLookupFileDialog,
+    ExportLookup(String),
+    ConfirmStatistics,
+    ExportStatisticsFileDialog,
+    ExportStatistics(String),
+    ConfirmMedia,
+    ExportMediaFileDialog,
+    ExportMedia(String),
+    Query(String),
+    ConfirmQuery,
+    ExportQueryFileDialog,
+    ExportQuery(String),
+    ConfirmReport(Report),
+    // The position of the kind shown alone, or everything
+    ReportFilter(Report, Option<usize>),
+    ExportReportFileDialog(Report),
+    ExportReport(Report, String)
 }
 
 pub type Element<'a> = iced::Element<'a, Message>;
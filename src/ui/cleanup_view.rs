@@ -0,0 +1,34 @@
+use super::base::*;
+use super::report_view::{cell, item_link, report_filters, report_footer, report_table, size_string};
+use crate::analysis::CleanupKind;
+use iced::widget::{button, column, horizontal_space, row, text};
+use iced::{Alignment, Length};
+
+pub fn cleanup_view(state: &State) -> Element<'_> {
+    let top = row![
+        text("Junk files, build caches, extra copies and stale large files that could be deleted, nothing is deleted here"),
+        horizontal_space(),
+        button("Find candidates").on_press(Message::ConfirmReport(Report::Cleanup))
+    ]
+    .align_y(Alignment::Center);
+    let Some(report) = &state.cleanup_report else {
+        return column![top, Container::new(text("Candidates will be ranked here by reclaimable space")).center(Length::Fill)].into();
+    };
+    let kinds = report_filters(Report::Cleanup, &CleanupKind::ALL, state.cleanup_filter, |kind| {
+        format!("{}: {} ({})", kind.title(), report.count(kind), size_string(report.reclaimable(kind)))
+    });
+    let candidates: Vec<_> = report.candidates.iter().filter(|candidate| state.cleanup_filter.is_none_or(|kind| candidate.kind == kind)).collect();
+    let rows = candidates.iter().map(|candidate| vec![
+        cell(candidate.kind.title()),
+        item_link(candidate.path.clone(), candidate.id),
+        cell(size_string(candidate.reclaimable)),
+        cell(&candidate.reason),
+    ]);
+    column![
+        top,
+        kinds,
+        report_table(&["Kind", "Path", "Reclaimable", "Reason"], rows),
+        report_footer(format!("{} candidates, {} reclaimable", report.candidates.len(), size_string(report.reclaimable)), Report::Cleanup)
+    ]
+    .into()
+}
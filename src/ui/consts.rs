@@ -1,4 +1,9 @@
 
 
 pub const ERROR_COLOR: [f32; 3] = [1., 0., 0.];
-pub const DIR_COLOR: [f32; 3] = [0., 1., 0.];
\ No newline at end of file
+pub const DIR_COLOR: [f32; 3] = [0., 1., 0.];
+// Reports can be huge, only this many rows are shown
+pub const REPORT_ROWS: usize = 1000;
+pub const FONT: iced::Font = iced::Font::with_name("Source Han Sans SC");
+// Height of a bar in the charts
+pub const CHART_BAR: f32 = 20.;
@@ -0,0 +1,65 @@
+use super::base::*;
+use super::consts::DIR_COLOR;
+use super::report_view::{cell, item_link, report_table};
+use crate::analysis::DiffKind;
+use iced::widget::{button, checkbox, column, container, horizontal_space, row, text, text_input};
+use iced::{Alignment, Length};
+
+pub fn diff_view(state: &State) -> Element<'_> {
+    let top = row![
+        text("Compare with:").width(80),
+        text_input("An older record", &state.diff_path).on_input(Message::DiffPath),
+        button("Choose").on_press(Message::DiffPathFileDialog),
+        // Skips directories with the same Merkle hash, but only sees content
+        checkbox("Content only", state.diff_content_only).on_toggle(Message::DiffContentOnly),
+        button("Diff").on_press(Message::ConfirmDiff)
+    ]
+    .align_y(Alignment::Center);
+    let Some(report) = &state.diff_report else {
+        return column![top, Container::new(text("Compare an older record with the current one")).center(Length::Fill)].into();
+    };
+    let filter = state.diff_filter.as_deref().unwrap_or(".");
+    let rollups = report.rollups.iter().map(|(path, count)| vec![
+        button(text(path.clone()).wrapping(text::Wrapping::None).color(DIR_COLOR))
+            .style(button::text)
+            .padding(0)
+            .on_press(Message::DiffFilter(path.clone()))
+            .into(),
+        cell(count),
+    ]);
+    let entries: Vec<_> = report.entries.iter().filter(|entry| filter == "." || entry.path().strip_prefix(filter).is_some_and(|rest| rest.starts_with('/'))).collect();
+    let rows = entries.iter().map(|entry| vec![
+        cell(kind_string(&entry.kind)),
+        cell(entry.old.as_ref().map(|old| old.path.as_str()).unwrap_or_default()),
+        match &entry.new {
+            Some(new) => item_link(new.path.clone(), new.id),
+            None => cell(""),
+        },
+        cell(entry.changes.join(", ")),
+    ]);
+    column![
+        top,
+        row![
+            container(report_table(&["Directory", "Changes"], rollups)).width(Length::FillPortion(1)),
+            container(report_table(&["Change", "Old path", "New path", "Details"], rows)).width(Length::FillPortion(3))
+        ],
+        row![
+            text(format!("{} changes under {}", entries.len(), filter)),
+            horizontal_space(),
+            button("Export report").on_press(Message::ExportDiffFileDialog)
+        ]
+        .align_y(Alignment::Center)
+    ]
+    .into()
+}
+
+fn kind_string(kind: &DiffKind) -> &'static str {
+    match kind {
+        DiffKind::Added => "Added",
+        DiffKind::Removed => "Removed",
+        DiffKind::Modified => "Modified",
+        DiffKind::Renamed => "Renamed",
+        DiffKind::Moved => "Moved",
+        DiffKind::MetadataOnly => "Metadata only",
+    }
+}
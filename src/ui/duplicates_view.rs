@@ -0,0 +1,37 @@
+use super::base::*;
+use super::report_view::{cell, item_link, report_table, size_string};
+use iced::widget::{button, column, horizontal_space, row, text};
+use iced::{Alignment, Length};
+
+pub fn duplicates_view(state: &State) -> Element<'_> {
+    let top = row![
+        text("Regular files with the same size and checksum, hard links excluded"),
+        horizontal_space(),
+        button("Find duplicates").on_press(Message::ConfirmDuplicates)
+    ]
+    .align_y(Alignment::Center);
+    let Some(report) = &state.duplicate_report else {
+        return column![top, Container::new(text("Duplicates will be listed here")).center(Length::Fill)].into();
+    };
+    let copies: Vec<_> = report.groups.iter().flat_map(|group| group.copies.iter().enumerate().map(move |(i, copy)| (i, group, copy))).collect();
+    let rows = copies.iter().map(|(i, group, copy)| {
+        let first = *i == 0;
+        vec![
+            cell(if first { size_string(group.wasted) } else { String::new() }),
+            cell(if first { group.size.map(size_string).unwrap_or_default() } else { String::new() }),
+            cell(if first { group.md5.as_str() } else { "" }),
+            item_link(copy.path.clone(), copy.id),
+        ]
+    });
+    column![
+        top,
+        report_table(&["Wasted", "Size", "MD5", "Path"], rows),
+        row![
+            text(format!("{} groups, {} wasted", report.groups.len(), size_string(report.wasted))),
+            horizontal_space(),
+            button("Export report").on_press(Message::ExportDuplicatesFileDialog)
+        ]
+        .align_y(Alignment::Center)
+    ]
+    .into()
+}
@@ -0,0 +1,31 @@
+use super::base::*;
+use super::report_view::{cell, item_link, report_filters, report_footer, report_table};
+use crate::analysis::HygieneKind;
+use iced::widget::{button, column, horizontal_space, row, text};
+use iced::{Alignment, Length};
+
+pub fn hygiene_view(state: &State) -> Element<'_> {
+    let top = row![
+        text("Empty directories, zero-byte files, broken or external symlinks, inaccessible items and truncated directories"),
+        horizontal_space(),
+        button("Check").on_press(Message::ConfirmReport(Report::Hygiene))
+    ]
+    .align_y(Alignment::Center);
+    let Some(report) = &state.hygiene_report else {
+        return column![top, Container::new(text("Problems will be listed here")).center(Length::Fill)].into();
+    };
+    let kinds = report_filters(Report::Hygiene, &HygieneKind::ALL, state.hygiene_filter, |kind| format!("{}: {}", kind.title(), report.count(kind)));
+    let entries: Vec<_> = report.entries.iter().filter(|entry| state.hygiene_filter.is_none_or(|kind| entry.kind == kind)).collect();
+    let rows = entries.iter().map(|entry| vec![
+        cell(entry.kind.title()),
+        item_link(entry.path.clone(), entry.id),
+        cell(&entry.detail),
+    ]);
+    column![
+        top,
+        kinds,
+        report_table(&["Kind", "Path", "Detail"], rows),
+        report_footer(format!("{} problems", report.entries.len()), Report::Hygiene)
+    ]
+    .into()
+}
@@ -0,0 +1,92 @@
+use super::base::*;
+use super::report_view::{cell, report_table, size_string};
+use crate::analysis::LookupReport;
+use crate::record::TableReport;
+use crate::scan::stringify_time;
+use iced::widget::{button, column, horizontal_space, row, text, text_input};
+use iced::{Alignment, Length};
+
+pub fn library_view(state: &State) -> Element<'_> {
+    let scans = match state.library.scans.is_empty() {
+        true => "No records in the library".to_owned(),
+        false => state.library.scans.iter().map(|scan| scan.name.as_str()).collect::<Vec<_>>().join(", "),
+    };
+    let top = column![
+        row![
+            text("Library:").width(80),
+            text(scans).width(Length::Fill),
+            button("Add records").on_press(Message::LibraryAddFileDialog),
+            button("Clear").on_press(Message::LibraryClear)
+        ]
+        .align_y(Alignment::Center),
+        row![
+            text("Where is:").width(80),
+            text_input("MD5 of a file", &state.library_query).on_input(Message::LibraryQuery).on_submit(Message::ConfirmLibraryQuery),
+            button("Search").on_press(Message::ConfirmLibraryQuery),
+            button("Find duplicates").on_press(Message::ConfirmLibraryDuplicates)
+        ]
+        .align_y(Alignment::Center),
+        row![
+            text("Look up:").width(80),
+            text("Choose a local file or drop it on the window").width(Length::Fill),
+            button("Choose file").on_press(Message::LookupFileDialog)
+        ]
+        .align_y(Alignment::Center)
+    ];
+    if let Some(report) = &state.lookup_report {
+        return column![top, lookup_view(report)].into();
+    }
+    let Some(report) = &state.library_report else {
+        return column![top, Container::new(text("Search the records of many drives by content")).center(Length::Fill)].into();
+    };
+    let locations: Vec<_> = report.groups.iter().flat_map(|group| group.locations.iter().enumerate().map(move |(i, location)| (i, group, location))).collect();
+    let rows = locations.iter().map(|(i, group, location)| {
+        let first = *i == 0;
+        vec![
+            cell(if first { group.md5.as_str() } else { "" }),
+            cell(if first { group.size.map(size_string).unwrap_or_default() } else { String::new() }),
+            cell(&location.scan),
+            cell(&location.root),
+            cell(&location.path),
+        ]
+    });
+    column![
+        top,
+        report_table(&["MD5", "Size", "Scan", "Root", "Path"], rows),
+        row![
+            text(format!("{} contents found", report.groups.len())),
+            horizontal_space(),
+            button("Export report").on_press(Message::ExportLibraryFileDialog)
+        ]
+        .align_y(Alignment::Center)
+    ]
+    .into()
+}
+
+fn lookup_view(report: &LookupReport) -> Element<'_> {
+    let matches = report.matches.iter().map(|location| ("Same content", location));
+    let same_name = report.same_name.iter().map(|location| ("Same name", location));
+    let locations: Vec<_> = matches.chain(same_name).collect();
+    let rows = locations.iter().map(|(kind, location)| vec![
+        cell(kind),
+        cell(&location.scan),
+        cell(&location.root),
+        cell(&location.path),
+        cell(stringify_time(location.created)),
+        cell(stringify_time(location.modified)),
+    ]);
+    let summary = match (report.matches.len(), report.same_name.len()) {
+        (0, 0) => format!("{} ({}) is not in the library", report.path, report.md5),
+        (0, count) => format!("The content of {} is not in the library, {} files have the same name", report.path, count),
+        (count, _) => format!("{} ({}) found at {} places", report.path, report.md5, count),
+    };
+    column![
+        report_table(LookupReport::TITLES, rows),
+        row![
+            text(summary).width(Length::Fill),
+            button("Export report").on_press(Message::ExportLookupFileDialog)
+        ]
+        .align_y(Alignment::Center)
+    ]
+    .into()
+}
@@ -0,0 +1,79 @@
+use super::base::*;
+use super::consts::REPORT_ROWS;
+use super::report_view::{cell, item_link};
+use crate::analysis::{MediaFolder, MediaTally, ProbeFailure};
+use iced::widget::{button, column, horizontal_space, row, scrollable, text, Column, Row};
+use iced::{Alignment, Length};
+
+fn duration_string(duration: i64) -> String {
+    let seconds = duration / 1_000_000;
+    format!("{}:{:02}:{:02}", seconds / 3600, seconds / 60 % 60, seconds % 60)
+}
+
+// Columns of cells, as tables inside one scrollable can not fill the height
+fn table<'a>(title: &'a str, titles: &[&str], rows: Vec<Vec<Element<'a>>>) -> Element<'a> {
+    let mut cols: Vec<Vec<Element>> = titles.iter().map(|title| vec![text(title.to_string()).into()]).collect();
+    for row in rows {
+        for (i, cell) in row.into_iter().enumerate() {
+            cols[i].push(cell);
+        }
+    }
+    let cols = cols.into_iter().map(|col| Element::from(Column::from_vec(col).padding(5).clip(true)));
+    column![text(title), Row::with_children(cols)]
+        .spacing(5)
+        .width(Length::Fill)
+        .into()
+}
+
+fn tallies<'a>(title: &'a str, tallies: &'a [MediaTally]) -> Element<'a> {
+    let rows = tallies.iter().take(REPORT_ROWS).map(|tally| vec![cell(&tally.name), cell(tally.count), cell(duration_string(tally.duration))]);
+    table(title, &["Name", "Files", "Duration"], rows.collect())
+}
+
+fn folders(folders: &[MediaFolder]) -> Element<'_> {
+    let rows = folders.iter().take(REPORT_ROWS)
+        .map(|folder| vec![cell(duration_string(folder.duration)), cell(folder.count), item_link(folder.path.clone(), folder.id)]);
+    table("Folders", &["Duration", "Files", "Path"], rows.collect())
+}
+
+fn failures(failures: &[ProbeFailure]) -> Element<'_> {
+    let rows = failures.iter().take(REPORT_ROWS).map(|failure| vec![item_link(failure.path.clone(), failure.id), cell(&failure.error)]);
+    table("Probe failures", &["Path", "Error"], rows.collect())
+}
+
+pub fn media_view(state: &State) -> Element<'_> {
+    let top = row![
+        text("Audio and video files probed by ffmpeg during the scan"),
+        horizontal_space(),
+        button("Compute media report").on_press(Message::ConfirmMedia)
+    ]
+    .align_y(Alignment::Center);
+    let Some(report) = &state.media_report else {
+        return column![top, Container::new(text("Media report will be shown here")).center(Length::Fill)].into();
+    };
+    if report.count == 0 && report.failures.is_empty() && report.other_failures == 0 {
+        return column![top, Container::new(text("No media found, the record may be scanned without media metadata")).center(Length::Fill)].into();
+    }
+    let sections = column![
+        row![tallies("Video codecs", &report.video_codecs), tallies("Audio codecs", &report.audio_codecs)].spacing(20),
+        row![tallies("Resolutions", &report.resolutions), tallies("Artists and albums", &report.albums)].spacing(20),
+        folders(&report.folders),
+        failures(&report.failures),
+    ]
+    .spacing(20)
+    .padding(5);
+    column![
+        top,
+        Container::new(scrollable(sections)).height(Length::Fill),
+        row![
+            text(format!(
+                "{} media files, {} in total, {} probe failures of media files and {} of other files",
+                report.count, duration_string(report.duration), report.failures.len(), report.other_failures
+            )),
+            horizontal_space(),
+            button("Export report").on_press(Message::ExportMediaFileDialog)
+        ]
+        .align_y(Alignment::Center)
+    ]
+    .into()
+}
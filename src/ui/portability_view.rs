@@ -0,0 +1,42 @@
+use super::base::*;
+use super::report_view::{cell, item_link, report_filters, report_footer, report_table};
+use crate::analysis::PortabilityKind;
+use iced::widget::{button, column, horizontal_space, row, text};
+use iced::{Alignment, Length};
+
+pub fn portability_view(state: &State) -> Element<'_> {
+    let top = row![
+        text("Names and paths that break on Windows, exFAT, NTFS or case-insensitive macOS volumes, paths are counted from the scan root"),
+        horizontal_space(),
+        button("Check").on_press(Message::ConfirmReport(Report::Portability))
+    ]
+    .align_y(Alignment::Center);
+    let Some(report) = &state.portability_report else {
+        return column![top, Container::new(text("Problems will be listed here by directory")).center(Length::Fill)].into();
+    };
+    let kinds = report_filters(Report::Portability, &PortabilityKind::ALL, state.portability_filter, |kind| format!("{}: {}", kind.title(), report.count(kind)));
+    // The directory is only named on its first row
+    let filter = state.portability_filter;
+    let entries: Vec<_> = report.dirs.iter()
+        .flat_map(|dir| dir.entries.iter()
+            .filter(move |entry| filter.is_none_or(|kind| entry.kind == kind))
+            .enumerate()
+            .map(move |(i, entry)| (dir, i == 0, entry)))
+        .collect();
+    let rows = entries.iter().map(|(dir, first, entry)| vec![
+        match first {
+            true => item_link(dir.path.clone(), dir.id),
+            false => cell(""),
+        },
+        cell(entry.kind.title()),
+        item_link(entry.name.clone(), entry.id),
+        cell(&entry.detail),
+    ]);
+    column![
+        top,
+        kinds,
+        report_table(&["Directory", "Kind", "Name", "Detail"], rows),
+        report_footer(format!("{} problems in {} directories", report.dirs.iter().map(|dir| dir.entries.len()).sum::<usize>(), report.dirs.len()), Report::Portability)
+    ]
+    .into()
+}
@@ -0,0 +1,40 @@
+use super::base::*;
+use super::consts::ERROR_COLOR;
+use super::report_view::{cell, item_link, report_table, size_string};
+use crate::scan::stringify_time;
+use iced::widget::{button, column, horizontal_space, row, text, text_input};
+use iced::{Alignment, Length};
+
+pub fn query_view(state: &State) -> Element<'_> {
+    let top = row![
+        text("Query:").width(80),
+        text_input("type = regular and ext like \"mp*\" and size > 2GB and modified < 2022", &state.query).on_input(Message::Query).on_submit(Message::ConfirmQuery),
+        button("Run").on_press(Message::ConfirmQuery)
+    ]
+    .align_y(Alignment::Center);
+    if let Some(err) = &state.query_error {
+        return column![top, Container::new(text(err).color(ERROR_COLOR)).center(Length::Fill)].into();
+    }
+    let Some(report) = &state.query_report else {
+        let help = "Compare name, ext, type, size, created, modified, accessed, md5 or meta.<key> with =, !=, <, <=, >, >=, like (glob) or matches (regex), \
+            and combine them with and, or, not and parentheses";
+        return column![top, Container::new(text(help)).center(Length::Fill)].into();
+    };
+    let rows = report.matches.iter().map(|found| vec![
+        item_link(found.path.clone(), found.id),
+        cell(&found.file_type),
+        cell(found.size.map(size_string).unwrap_or_default()),
+        cell(stringify_time(found.modified)),
+    ]);
+    column![
+        top,
+        report_table(&["Path", "Type", "Size", "Modified"], rows),
+        row![
+            text(format!("{} items match", report.matches.len())),
+            horizontal_space(),
+            button("Export report").on_press(Message::ExportQueryFileDialog)
+        ]
+        .align_y(Alignment::Center)
+    ]
+    .into()
+}
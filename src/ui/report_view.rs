@@ -0,0 +1,73 @@
+use super::base::*;
+use super::consts::{DIR_COLOR, REPORT_ROWS};
+use iced::widget::{button, column, horizontal_space, row, scrollable, text, Column, Row};
+use iced::{Alignment, Length};
+
+pub fn item_link<'a>(content: String, id: usize) -> Element<'a> {
+    button(text(content).wrapping(text::Wrapping::None).color(DIR_COLOR))
+        .style(button::text)
+        .padding(0)
+        .on_press(Message::Reveal(id))
+        .into()
+}
+
+pub fn cell<'a>(content: impl ToString) -> Element<'a> {
+    text(content.to_string()).wrapping(text::Wrapping::None).into()
+}
+
+pub fn report_table<'a>(titles: &[&str], rows: impl ExactSizeIterator<Item = Vec<Element<'a>>>) -> Element<'a> {
+    let total = rows.len();
+    let mut cols: Vec<Vec<Element>> = titles.iter().map(|title| vec![text(title.to_string()).into()]).collect();
+    for row in rows.take(REPORT_ROWS) {
+        for (i, cell) in row.into_iter().enumerate() {
+            cols[i].push(cell);
+        }
+    }
+    let elems: Vec<_> = cols.into_iter().map(|col| Element::from(
+        Column::from_vec(col).padding(5).clip(true)
+    )).collect();
+    let scroll = scrollable(Row::from_vec(elems).push(horizontal_space()));
+    column![Container::new(scroll).height(Length::Fill).clip(true)]
+        .push_maybe((total > REPORT_ROWS).then(|| text(format!("Showing {} of {} rows", REPORT_ROWS, total))))
+        .into()
+}
+
+// Each kind is a filter, the selected one again shows everything
+pub fn report_filters<'a, K: Copy + PartialEq>(report: Report, kinds: &[K], selected: Option<K>, label: impl Fn(K) -> String) -> Element<'a> {
+    row(kinds.iter().enumerate().map(|(i, kind)| {
+        let selected = selected == Some(*kind);
+        button(text(label(*kind)))
+            .style(move |theme, status| match selected {
+                true => button::primary(theme, status),
+                false => button::secondary(theme, status),
+            })
+            .on_press(Message::ReportFilter(report, (!selected).then_some(i)))
+            .into()
+    }))
+    .spacing(5)
+    .into()
+}
+
+pub fn report_footer<'a>(summary: String, report: Report) -> Element<'a> {
+    row![
+        text(summary),
+        horizontal_space(),
+        button("Export report").on_press(Message::ExportReportFileDialog(report))
+    ]
+    .align_y(Alignment::Center)
+    .into()
+}
+
+pub fn size_string(size: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+    let mut value = size as f64;
+    let mut unit = 0;
+    while value >= 1024. && unit + 1 < UNITS.len() {
+        value /= 1024.;
+        unit += 1;
+    }
+    match unit {
+        0 => format!("{} B", size),
+        _ => format!("{:.1} {}", value, UNITS[unit]),
+    }
+}
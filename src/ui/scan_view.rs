@@ -1,50 +1,113 @@
 use super::base::*;
-use super::consts::{ERROR_COLOR, DIR_COLOR};
-use crate::scan::{info_string, FileItem, FileType};
-use iced::widget::{button, horizontal_space, scrollable, text, column, Column, Row};
-use iced::Length;
+use super::consts::{ERROR_COLOR, DIR_COLOR, REPORT_ROWS};
+use super::report_view::{item_link, report_table, size_string};
+use super::sunburst_view::{sunburst_view, SunburstMeasure};
+use super::tree_view::tree_view;
+use super::treemap_view::{treemap_view, TreemapColor};
+use crate::analysis::DiskUsage;
+use crate::record::{self, RecordError};
+use crate::scan::{info_string, FileItem, FileType, NameSearch};
+use iced::widget::{button, checkbox, container, horizontal_space, pick_list, row, scrollable, text, text_input, tooltip, column, Column, Row};
+use iced::{Alignment, Length};
+use std::fmt::Display;
 
 #[derive(Debug, Clone)]
 pub enum FileViewError {
     InvalidScanPath,
     InvalidLoadPath,
     InvalidLoadContent,
+    NewerRecordVersion(u32),
     ScanLimitReached,
     FileIoError(String),
+    CatalogError(String),
+    ManifestError(String),
 }
 
+impl From<RecordError> for FileViewError {
+    fn from(value: RecordError) -> Self {
+        match value {
+            RecordError::Io(err) => FileViewError::FileIoError(err),
+            RecordError::InvalidContent => FileViewError::InvalidLoadContent,
+            RecordError::NewerVersion(version) => FileViewError::NewerRecordVersion(version),
+            RecordError::Catalog(err) => FileViewError::CatalogError(err),
+            RecordError::Manifest(err) => FileViewError::ManifestError(err),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileViewMode {
+    List,
+    Treemap,
+    Sunburst,
+}
+
+impl FileViewMode {
+    pub const ALL: [FileViewMode; 3] = [FileViewMode::List, FileViewMode::Treemap, FileViewMode::Sunburst];
+}
+
+impl Display for FileViewMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            FileViewMode::List => "List",
+            FileViewMode::Treemap => "Treemap",
+            FileViewMode::Sunburst => "Sunburst",
+        })
+    }
+}
+
+// Only the first of the identical directories are named
+const TWIN_PATHS: usize = 5;
+
 pub fn scan_view(state: &State) -> Element<'_> {
     if state.file_view_error.is_some() {
         let text = match state.file_view_error.as_ref().unwrap() {
             FileViewError::InvalidScanPath => text("Scan path should be a folder and save path should be a file"),
             FileViewError::InvalidLoadPath => text("Load path should be a file"),
             FileViewError::InvalidLoadContent => text("Invalid content to be loaded"),
+            FileViewError::NewerRecordVersion(version) => text(format!("Record version {} is newer than the supported version {}", version, record::VERSION)),
             FileViewError::ScanLimitReached => text(format!("Scan limit reached: {}", state.scan_settings.scan_limit.unwrap())),
             FileViewError::FileIoError(err) => text(format!("File IO error: {}", err)),
+            FileViewError::CatalogError(err) => text(format!("Catalog error: {}", err)),
+            FileViewError::ManifestError(err) => text(format!("Manifest error: {}", err)),
         }
         .color(ERROR_COLOR);
         Container::new(text).center(Length::Fill).into()
-    } else if state.scan.is_none() {
+    } else if state.scan.is_none() && state.catalog.is_none() {
         Container::new(text("File items or error will be printed here")).center(Length::Fill).into()
     } else {
         let mut cols = Vec::new();
         for _ in 0..state.file_view_infos.len() { cols.push(Vec::new()); }
-        let scan = state.scan.as_ref().unwrap();
-        let items = &scan.items;
-        let curr = state.file_view_current;
-        let range = items[curr].childs().unwrap();
-        let mut items_view: Vec<_> = items[range].iter().collect();
+        let mut flags = Vec::new();
+        // A catalog is browsed straight from the database, so only the current directory is in memory
+        let (current, mut items_view, warning, description) = match (&state.scan, &state.catalog) {
+            (Some(scan), _) => {
+                let current = &scan.items[state.file_view_current];
+                let range = current.childs().unwrap();
+                (current, scan.items[range].iter().collect::<Vec<_>>(), &scan.warning, &scan.description)
+            }
+            (None, Some(view)) => (&view.current, view.childs.iter().collect(), &view.scan.warning, &view.scan.description),
+            (None, None) => unreachable!()
+        };
         items_view.sort_by(|itema, itemb| cmp_by_type(itema, itemb));
-        if let Some(parent) = items[curr].parent() {
+        if let Some(parent) = current.parent() {
             cols[0].push(dir_element("..".to_owned(), parent));
+            flags.push(text("").into());
             for i in 1..state.file_view_infos.len() {
                 cols[i].push(text("").into());
             }
         }
-        let file_view = if items[curr].parent().is_none() && items_view.is_empty() {
+        let file_view = if !state.name_query.is_empty() {
+            Container::new(name_matches_view(state)).height(Length::Fill)
+        } else if current.parent().is_none() && items_view.is_empty() {
             Container::new(text("No items")).center(Length::Fill)
+        } else if state.file_view_mode == FileViewMode::Treemap {
+            Container::new(treemap_view(state)).height(Length::Fill)
+        } else if state.file_view_mode == FileViewMode::Sunburst {
+            Container::new(sunburst_view(state)).height(Length::Fill)
         } else {
             for item in items_view {
+                flags.push(item_flags(state, item));
                 for i in 0..state.file_view_infos.len() {
                     let info = info_string(item, &state.file_view_infos[i]);
                     match item.file_type() {
@@ -53,17 +116,77 @@ pub fn scan_view(state: &State) -> Element<'_> {
                     }
                 }
             }
-            let elems: Vec<_> = cols.into_iter().map(|col| Element::from(
+            // Only shown once timestamps are checked or directories have twins
+            let flags = (state.timestamp_report.is_some() || !state.dir_twins.is_empty()).then_some(flags);
+            let elems: Vec<_> = flags.into_iter().chain(cols).map(|col| Element::from(
                 Column::from_vec(col).padding(5).clip(true)
             )).collect();
             let scroll = scrollable(Row::from_vec(elems).push(horizontal_space()));
             Container::new(scroll).height(Length::Fill).clip(true)
         };
-        column![file_view]
-            .push_maybe((&scan.warning != "").then(|| text(&scan.warning).color(ERROR_COLOR)))
-            .push(text(&scan.description))
-            .into()
+        let search = row![
+            pick_list(FileViewMode::ALL, Some(state.file_view_mode), Message::FileViewMode),
+            text_input("Search names", &state.name_query).on_input(Message::NameQuery),
+            pick_list(NameSearch::ALL, Some(state.name_search), Message::NameSearch),
+            checkbox("Match case", state.name_match_case).on_toggle(Message::NameMatchCase)
+        ]
+        .push_maybe((state.file_view_mode == FileViewMode::Treemap).then(|| pick_list(TreemapColor::ALL, Some(state.treemap_color), Message::TreemapColor)))
+        .push_maybe((state.file_view_mode == FileViewMode::Sunburst).then(|| pick_list(SunburstMeasure::ALL, Some(state.sunburst_measure), Message::SunburstMeasure)))
+        .spacing(5)
+        .align_y(Alignment::Center);
+        let main = column![search, file_view]
+            .push_maybe(state.catalog.as_ref().map(|view| pick_list(view.scans.as_slice(), Some(&view.scan), Message::CatalogScan)))
+            .push_maybe((warning != "").then(|| text(warning).color(ERROR_COLOR)))
+            .push(text(description));
+        row![tree_view(state), main].spacing(5).into()
+    }
+}
+
+fn name_matches_view(state: &State) -> Element<'_> {
+    let Some(index) = &state.name_index else {
+        return Container::new(text("Names are not indexed")).center(Length::Fill).into();
+    };
+    let rows = state.name_matches.iter().map(|&id| vec![item_link(index.path(id), id)]);
+    let summary = match state.name_matches.len() {
+        REPORT_ROWS => format!("Only the first {} matches are shown", REPORT_ROWS),
+        count => format!("{} matches", count),
+    };
+    column![report_table(&["Path"], rows), text(summary)].into()
+}
+
+// Timestamp anomalies and directories with the same contents elsewhere
+fn item_flags<'a>(state: &'a State, item: &FileItem) -> Element<'a> {
+    let flag = |content: Element<'a>, tip: String| tooltip(content, container(text(tip)).padding(5).style(container::rounded_box), tooltip::Position::Right);
+    let anomalies = state.timestamp_report.as_ref().map(|report| report.kinds(item.id())).filter(|kinds| !kinds.is_empty());
+    let anomaly = anomalies.map(|kinds| flag(text("!").color(ERROR_COLOR).into(), kinds.iter().map(|kind| kind.title()).collect::<Vec<_>>().join(", ")));
+    let twins = item.dir_hash().and_then(|hash| state.dir_twins.get(hash));
+    let twin = twins.map(|twins| {
+        let others: Vec<_> = twins.iter().filter(|id| **id != item.id()).collect();
+        let mut paths: Vec<_> = others.iter().take(TWIN_PATHS).map(|id| state.name_index.as_ref().map(|index| index.path(**id)).unwrap_or_default()).collect();
+        if others.len() > TWIN_PATHS {
+            paths.push(format!("and {} more", others.len() - TWIN_PATHS));
+        }
+        flag(text("=").color(DIR_COLOR).into(), format!("Same contents as {}", paths.join(", ")))
+    });
+    row![].push_maybe(anomaly).push_maybe(twin).spacing(2).into()
+}
+
+// The current directory below its ancestors, as the charts have no `..` entry
+pub(super) fn breadcrumb(usage: &DiskUsage, id: usize) -> Element<'static> {
+    let ancestors = usage.ancestors(id);
+    let mut crumbs = Row::new().spacing(5).align_y(Alignment::Center);
+    for (i, &ancestor) in ancestors.iter().enumerate() {
+        let name = match i {
+            0 => ".".to_owned(),
+            _ => usage.nodes[ancestor].name.clone(),
+        };
+        crumbs = match ancestor == id {
+            true => crumbs.push(text(name)),
+            false => crumbs.push(dir_element(name, ancestor)).push(text("/")),
+        };
     }
+    let node = &usage.nodes[id];
+    crumbs.push(text(format!("({} in {} items)", size_string(node.size), node.count - 1))).into()
 }
 
 fn cmp_by_type(itema: &FileItem, itemb: &FileItem) -> std::cmp::Ordering {
@@ -78,7 +201,7 @@ fn cmp_by_type(itema: &FileItem, itemb: &FileItem) -> std::cmp::Ordering {
     }
 }
 
-fn dir_element(content: String, target: usize) -> Element<'static> {
+pub(super) fn dir_element(content: String, target: usize) -> Element<'static> {
     button(text(content).wrapping(text::Wrapping::None).color(DIR_COLOR))
         .style(button::text)
         .padding(0)
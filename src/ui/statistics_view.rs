@@ -0,0 +1,99 @@
+use super::base::*;
+use super::consts::{CHART_BAR, FONT};
+use super::report_view::{item_link, size_string};
+use crate::analysis::{Ranked, Tally};
+use iced::widget::{button, canvas, column, horizontal_space, row, scrollable, text, Column};
+use iced::{mouse, Alignment, Length, Point, Rectangle, Renderer, Size, Theme};
+
+// Extensions are a long tail, only the largest ones are charted
+const CHART_EXTENSIONS: usize = 15;
+const LABEL_WIDTH: f32 = 90.;
+const VALUE_WIDTH: f32 = 150.;
+
+struct BarChart<'a> {
+    tallies: &'a [Tally],
+    // What the bars are scaled by
+    measure: fn(&Tally) -> u64
+}
+
+impl canvas::Program<Message> for BarChart<'_> {
+    type State = ();
+
+    fn draw(&self, _state: &(), renderer: &Renderer, theme: &Theme, bounds: Rectangle, _cursor: mouse::Cursor) -> Vec<canvas::Geometry> {
+        let mut frame = canvas::Frame::new(renderer, bounds.size());
+        let palette = theme.palette();
+        let max = self.tallies.iter().map(self.measure).max().unwrap_or_default().max(1);
+        let full = (bounds.width - LABEL_WIDTH - VALUE_WIDTH).max(0.);
+        for (i, tally) in self.tallies.iter().enumerate() {
+            let y = i as f32 * CHART_BAR;
+            let width = full * ((self.measure)(tally) as f32 / max as f32);
+            frame.fill_rectangle(Point::new(LABEL_WIDTH, y + 2.), Size::new(width.max(1.), CHART_BAR - 4.), palette.primary);
+            let label = |content: String, x: f32| canvas::Text {
+                content,
+                position: Point::new(x, y + 3.),
+                color: palette.text,
+                size: 13.into(),
+                font: FONT,
+                ..Default::default()
+            };
+            frame.fill_text(label(tally.name.clone(), 0.));
+            frame.fill_text(label(format!("{} ({})", size_string(tally.size), tally.count), LABEL_WIDTH + width + 5.));
+        }
+        vec![frame.into_geometry()]
+    }
+}
+
+fn chart<'a>(title: &'a str, tallies: &'a [Tally], measure: fn(&Tally) -> u64) -> Element<'a> {
+    column![
+        text(title),
+        canvas(BarChart { tallies, measure }).width(Length::Fill).height(tallies.len() as f32 * CHART_BAR)
+    ]
+    .spacing(5)
+    .width(Length::Fill)
+    .into()
+}
+
+fn ranking<'a>(title: &'a str, ranked: &'a [Ranked]) -> Element<'a> {
+    let rows = ranked.iter().map(|ranked| Element::from(row![
+        text(size_string(ranked.size)).width(80),
+        item_link(ranked.path.clone(), ranked.id)
+    ]));
+    column![text(title), Column::with_children(rows)]
+        .spacing(5)
+        .width(Length::Fill)
+        .into()
+}
+
+pub fn statistics_view(state: &State) -> Element<'_> {
+    let top = row![
+        text("Space usage of the whole record"),
+        horizontal_space(),
+        button("Compute statistics").on_press(Message::ConfirmStatistics)
+    ]
+    .align_y(Alignment::Center);
+    let Some(statistics) = &state.statistics else {
+        return column![top, Container::new(text("Statistics will be shown here")).center(Length::Fill)].into();
+    };
+    let extensions = &statistics.extensions[..statistics.extensions.len().min(CHART_EXTENSIONS)];
+    // Histograms are by the number of files, the others by the space they take
+    let (by_size, by_count): (fn(&Tally) -> u64, fn(&Tally) -> u64) = (|tally| tally.size, |tally| tally.count);
+    let charts = column![
+        row![chart("File types", &statistics.types, by_size), chart("Categories", &statistics.categories, by_size)].spacing(20),
+        row![chart("Extensions", extensions, by_size), chart("Sizes", &statistics.sizes, by_count)].spacing(20),
+        chart("Modification years", &statistics.years, by_count),
+        row![ranking("Largest files", &statistics.largest_files), ranking("Largest directories", &statistics.largest_dirs)].spacing(20),
+    ]
+    .spacing(20)
+    .padding(5);
+    column![
+        top,
+        Container::new(scrollable(charts)).height(Length::Fill),
+        row![
+            text(format!("{} regular files, {}", statistics.total.count, size_string(statistics.total.size))),
+            horizontal_space(),
+            button("Export report").on_press(Message::ExportStatisticsFileDialog)
+        ]
+        .align_y(Alignment::Center)
+    ]
+    .into()
+}
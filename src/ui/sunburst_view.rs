@@ -0,0 +1,222 @@
+use super::base::*;
+use super::scan_view::breadcrumb;
+use super::treemap_view::{hover_box, label};
+use crate::analysis::{DiskUsage, UsageNode};
+use iced::widget::{canvas, column, text};
+use iced::{mouse, Color, Length, Point, Radians, Rectangle, Renderer, Size, Theme};
+use std::cell::{Ref, RefCell};
+use std::f32::consts::{FRAC_PI_2, TAU};
+use std::fmt::Display;
+
+// Rings around the current directory, deeper ones are reached by clicking
+const RINGS: usize = 6;
+// Thinner segments are left out with everything inside them
+const MIN_SWEEP: f32 = 0.005;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SunburstMeasure {
+    Size,
+    Count,
+}
+
+impl SunburstMeasure {
+    pub const ALL: [SunburstMeasure; 2] = [SunburstMeasure::Size, SunburstMeasure::Count];
+
+    fn value(&self, node: &UsageNode) -> u64 {
+        match self {
+            SunburstMeasure::Size => node.size,
+            SunburstMeasure::Count => node.count,
+        }
+    }
+}
+
+impl Display for SunburstMeasure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            SunburstMeasure::Size => "By size",
+            SunburstMeasure::Count => "By items",
+        })
+    }
+}
+
+// Angles are clockwise from the top
+#[derive(Debug, Clone, Copy)]
+struct Segment {
+    id: usize,
+    depth: usize,
+    start: f32,
+    end: f32
+}
+
+struct Layout {
+    generation: u64,
+    root: usize,
+    measure: SunburstMeasure,
+    segments: Vec<Segment>
+}
+
+#[derive(Default)]
+struct SunburstState {
+    cache: canvas::Cache,
+    hovering: bool,
+    // Laid out while drawing and kept for hovering and clicking
+    layout: RefCell<Option<Layout>>
+}
+
+struct Sunburst<'a> {
+    usage: &'a DiskUsage,
+    generation: u64,
+    root: usize,
+    measure: SunburstMeasure
+}
+
+impl Sunburst<'_> {
+    // Lays out again only when the scan, the directory or the measure change, the radii follow the size
+    fn segments<'s>(&self, state: &'s SunburstState) -> Ref<'s, Vec<Segment>> {
+        let key = (self.generation, self.root, self.measure);
+        let stale = state.layout.borrow().as_ref().is_none_or(|layout| (layout.generation, layout.root, layout.measure) != key);
+        if stale {
+            let mut segments = Vec::new();
+            ring(self.usage, self.measure, self.root, 1, 0., TAU, &mut segments);
+            *state.layout.borrow_mut() = Some(Layout { generation: self.generation, root: self.root, measure: self.measure, segments });
+            state.cache.clear();
+        }
+        Ref::map(state.layout.borrow(), |layout| &layout.as_ref().unwrap().segments)
+    }
+
+    // The current directory is the disc in the middle
+    fn hovered(&self, state: &SunburstState, bounds: Rectangle, cursor: mouse::Cursor) -> Option<usize> {
+        let position = cursor.position_in(bounds)?;
+        let (x, y) = (position.x - bounds.width / 2., position.y - bounds.height / 2.);
+        let depth = ((x * x + y * y).sqrt() / ring_width(bounds.size())) as usize;
+        if depth == 0 {
+            return Some(self.root);
+        }
+        let angle = (y.atan2(x) + FRAC_PI_2).rem_euclid(TAU);
+        self.segments(state).iter().find(|segment| segment.depth == depth && segment.start <= angle && angle < segment.end).map(|segment| segment.id)
+    }
+}
+
+impl canvas::Program<Message> for Sunburst<'_> {
+    type State = SunburstState;
+
+    fn update(&self, state: &mut SunburstState, event: &canvas::Event, bounds: Rectangle, cursor: mouse::Cursor) -> Option<canvas::Action<Message>> {
+        match event {
+            canvas::Event::Mouse(mouse::Event::CursorMoved { .. }) => {
+                let hovering = cursor.is_over(bounds);
+                let redraw = hovering || state.hovering;
+                state.hovering = hovering;
+                redraw.then(canvas::Action::request_redraw)
+            }
+            // A ring becomes the middle, the middle goes back to its parent
+            canvas::Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)) => {
+                let id = self.hovered(state, bounds, cursor)?;
+                let node = &self.usage.nodes[id];
+                let dir = if id == self.root || node.childs.is_none() { node.parent? } else { id };
+                Some(canvas::Action::publish(Message::FileViewCurrent(dir)).and_capture())
+            }
+            canvas::Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Right)) if cursor.is_over(bounds) => {
+                let parent = self.usage.nodes[self.root].parent?;
+                Some(canvas::Action::publish(Message::FileViewCurrent(parent)).and_capture())
+            }
+            _ => None,
+        }
+    }
+
+    fn draw(&self, state: &SunburstState, renderer: &Renderer, theme: &Theme, bounds: Rectangle, cursor: mouse::Cursor) -> Vec<canvas::Geometry> {
+        let palette = theme.palette();
+        let segments = self.segments(state);
+        let center = Point::new(bounds.width / 2., bounds.height / 2.);
+        let width = ring_width(bounds.size());
+        // A segment is a stroke along the middle of its ring
+        let arc = |segment: &Segment| canvas::Path::new(|builder| builder.arc(canvas::path::Arc {
+            center,
+            radius: (segment.depth as f32 + 0.5) * width,
+            start_angle: Radians(segment.start - FRAC_PI_2),
+            end_angle: Radians(segment.end - FRAC_PI_2),
+        }));
+        let chart = state.cache.draw(renderer, bounds.size(), |frame| {
+            frame.fill(&canvas::Path::circle(center, width - 1.), palette.primary);
+            let name = &self.usage.nodes[self.root].name;
+            let shown = name.chars().count().min((2. * width / 7.) as usize) as f32;
+            frame.fill_text(label(name, Point::new(center.x - shown * 3.5, center.y - 7.), 2. * width, palette.text));
+            for segment in segments.iter() {
+                let node = &self.usage.nodes[segment.id];
+                let color = hue((segment.start + segment.end) / 2., segment.depth, node.childs.is_some());
+                frame.stroke(&arc(segment), canvas::Stroke::default().with_color(color).with_width(width - 1.));
+            }
+        });
+        let mut overlay = canvas::Frame::new(renderer, bounds.size());
+        if let (Some(id), Some(position)) = (self.hovered(state, bounds, cursor), cursor.position_in(bounds)) {
+            let highlight = Color { a: 0.3, ..palette.text };
+            match segments.iter().find(|segment| segment.id == id) {
+                Some(segment) => overlay.stroke(&arc(segment), canvas::Stroke::default().with_color(highlight).with_width(width - 1.)),
+                None => overlay.fill(&canvas::Path::circle(center, width - 1.), highlight),
+            }
+            hover_box(&mut overlay, self.usage, id, position, palette);
+        }
+        vec![chart, overlay.into_geometry()]
+    }
+
+    fn mouse_interaction(&self, state: &SunburstState, bounds: Rectangle, cursor: mouse::Cursor) -> mouse::Interaction {
+        match self.hovered(state, bounds, cursor) {
+            Some(_) => mouse::Interaction::Pointer,
+            None => mouse::Interaction::default(),
+        }
+    }
+}
+
+fn ring_width(size: Size) -> f32 {
+    size.width.min(size.height) / 2. / (RINGS + 1) as f32
+}
+
+// Children share the angle of their parent by the measure, the largest first
+fn ring(usage: &DiskUsage, measure: SunburstMeasure, id: usize, depth: usize, start: f32, end: f32, segments: &mut Vec<Segment>) {
+    let Some(childs) = usage.nodes[id].childs.clone() else {
+        return;
+    };
+    if depth > RINGS {
+        return;
+    }
+    let mut values: Vec<_> = childs.map(|child| (child, measure.value(&usage.nodes[child]))).filter(|(_, value)| *value > 0).collect();
+    values.sort_by(|(ida, valuea), (idb, valueb)| valueb.cmp(valuea).then(ida.cmp(idb)));
+    let total: u64 = values.iter().map(|(_, value)| value).sum();
+    let mut angle = start;
+    for (child, value) in values {
+        let sweep = (end - start) * value as f32 / total as f32;
+        if sweep < MIN_SWEEP {
+            break;
+        }
+        segments.push(Segment { id: child, depth, start: angle, end: angle + sweep });
+        ring(usage, measure, child, depth + 1, angle, angle + sweep, segments);
+        angle += sweep;
+    }
+}
+
+// Hues go around the chart so that a subtree keeps the hue of its direction, files are paler
+fn hue(angle: f32, depth: usize, dir: bool) -> Color {
+    let sector = angle / TAU * 6.;
+    let saturation = if dir { 0.6 } else { 0.3 };
+    let value = 0.9 - depth as f32 * 0.07;
+    let chroma = value * saturation;
+    let x = chroma * (1. - (sector % 2. - 1.).abs());
+    let (r, g, b) = match sector as u32 {
+        0 => (chroma, x, 0.),
+        1 => (x, chroma, 0.),
+        2 => (0., chroma, x),
+        3 => (0., x, chroma),
+        4 => (x, 0., chroma),
+        _ => (chroma, 0., x),
+    };
+    let min = value - chroma;
+    Color::from_rgb(r + min, g + min, b + min)
+}
+
+pub fn sunburst_view(state: &State) -> Element<'_> {
+    let Some(usage) = &state.disk_usage else {
+        return Container::new(text("Sizes are not computed")).center(Length::Fill).into();
+    };
+    let root = state.file_view_current;
+    let sunburst = canvas(Sunburst { usage, generation: state.scan_generation, root, measure: state.sunburst_measure }).width(Length::Fill).height(Length::Fill);
+    column![breadcrumb(usage, root), sunburst, text("Click a ring to go into it and the middle to go back")].spacing(5).into()
+}
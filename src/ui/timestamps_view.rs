@@ -0,0 +1,32 @@
+use super::base::*;
+use super::report_view::{cell, item_link, report_filters, report_footer, report_table};
+use crate::analysis::AnomalyKind;
+use crate::scan::stringify_time;
+use iced::widget::{button, column, horizontal_space, row, text};
+use iced::{Alignment, Length};
+
+pub fn timestamps_view(state: &State) -> Element<'_> {
+    let top = row![
+        text("Times in the future, creation after modification, times before 1980, many files modified at once and whole second times"),
+        horizontal_space(),
+        button("Check").on_press(Message::ConfirmReport(Report::Timestamps))
+    ]
+    .align_y(Alignment::Center);
+    let Some(report) = &state.timestamp_report else {
+        return column![top, Container::new(text("Anomalies will be listed here")).center(Length::Fill)].into();
+    };
+    let kinds = report_filters(Report::Timestamps, &AnomalyKind::ALL, state.timestamp_filter, |kind| format!("{}: {}", kind.title(), report.count(kind)));
+    let entries: Vec<_> = report.entries.iter().filter(|entry| state.timestamp_filter.is_none_or(|kind| entry.kind == kind)).collect();
+    let rows = entries.iter().map(|entry| vec![
+        cell(entry.kind.title()),
+        item_link(entry.path.clone(), entry.id),
+        cell(&entry.detail),
+    ]);
+    column![
+        top,
+        kinds,
+        report_table(&["Kind", "Path", "Detail"], rows),
+        report_footer(format!("{} anomalies, the scan at {}", report.entries.len(), stringify_time(Some(report.reference))), Report::Timestamps)
+    ]
+    .into()
+}
@@ -0,0 +1,69 @@
+use super::base::*;
+use super::consts::DIR_COLOR;
+use iced::widget::{button, column, horizontal_space, row, scrollable, text, vertical_space};
+use iced::{Alignment, Length};
+
+pub const TREE_ROW: f32 = 20.;
+const TREE_INDENT: f32 = 12.;
+const TREE_WIDTH: f32 = 220.;
+// Rows are built for this height until the tree is first drawn
+const TREE_HEIGHT: f32 = 1200.;
+
+// A directory in sight, rows are in the order they are shown and children follow their parent
+#[derive(Debug, Clone)]
+pub struct TreeRow {
+    pub id: usize,
+    pub name: String,
+    pub depth: usize,
+    // Directories with nothing inside are not expanded
+    pub expandable: bool,
+    pub expanded: bool
+}
+
+pub fn tree_id() -> scrollable::Id {
+    scrollable::Id::new("tree")
+}
+
+pub fn tree_view(state: &State) -> Element<'_> {
+    // Only the rows in sight are built, as a directory may hold 100k others
+    let first = ((state.tree_offset / TREE_ROW) as usize).min(state.tree.len());
+    let height = if state.tree_height > 0. { state.tree_height } else { TREE_HEIGHT };
+    let last = (first + (height / TREE_ROW) as usize + 2).min(state.tree.len());
+    let content = column![vertical_space().height(first as f32 * TREE_ROW)]
+        .extend(state.tree[first..last].iter().map(|row| tree_row(state, row)))
+        .push(vertical_space().height((state.tree.len() - last) as f32 * TREE_ROW));
+    scrollable(content)
+        .id(tree_id())
+        .on_scroll(|viewport| Message::TreeScroll(viewport.absolute_offset().y, viewport.bounds().height))
+        .width(TREE_WIDTH)
+        .height(Length::Fill)
+        .into()
+}
+
+fn tree_row<'a>(state: &State, row: &'a TreeRow) -> Element<'a> {
+    let toggle = match (row.expandable, row.expanded) {
+        (false, _) => "",
+        (true, false) => "▸",
+        (true, true) => "▾",
+    };
+    let current = row.id == state.file_view_current;
+    row![
+        horizontal_space().width(row.depth as f32 * TREE_INDENT),
+        button(text(toggle))
+            .style(button::text)
+            .padding(0)
+            .width(TREE_INDENT)
+            .on_press_maybe(row.expandable.then_some(Message::TreeToggle(row.id))),
+        button(text(row.name.as_str()).wrapping(text::Wrapping::None).color_maybe((!current).then_some(DIR_COLOR)))
+            .style(move |theme, status| match current {
+                true => button::primary(theme, status),
+                false => button::text(theme, status),
+            })
+            .padding(0)
+            .on_press(Message::FileViewCurrent(row.id))
+    ]
+    .height(TREE_ROW)
+    .align_y(Alignment::Center)
+    .clip(true)
+    .into()
+}
@@ -0,0 +1,296 @@
+use super::base::*;
+use super::consts::FONT;
+use super::report_view::size_string;
+use super::scan_view::breadcrumb;
+use crate::analysis::DiskUsage;
+use iced::widget::{canvas, column, text, Row};
+use iced::{mouse, theme::Palette, Color, Length, Point, Rectangle, Renderer, Size, Theme};
+use std::{cell::{Ref, RefCell}, fmt::Display};
+
+// Levels below the current directory that are drawn, deeper ones are reached by clicking
+const DEPTH: usize = 3;
+// Smaller directories are drawn as a whole
+const MIN_NESTED: f32 = 30.;
+const HEADER: f32 = 16.;
+const PADDING: f32 = 2.;
+const DIR_TILE: [f32; 3] = [0.35, 0.35, 0.35];
+const CATEGORY_COLORS: [(&str, [f32; 3]); 7] = [
+    ("Image", [0.9, 0.6, 0.2]),
+    ("Video", [0.85, 0.3, 0.3]),
+    ("Audio", [0.7, 0.4, 0.8]),
+    ("Document", [0.3, 0.6, 0.9]),
+    ("Archive", [0.8, 0.8, 0.3]),
+    ("Code", [0.3, 0.75, 0.5]),
+    ("Other", [0.6, 0.6, 0.6]),
+];
+// By days since the last modification before the scan
+const AGE_COLORS: [(i64, &str, [f32; 3]); 5] = [
+    (30, "< 1 month", [0.2, 0.8, 0.3]),
+    (365, "< 1 year", [0.6, 0.8, 0.2]),
+    (3 * 365, "< 3 years", [0.9, 0.7, 0.2]),
+    (10 * 365, "< 10 years", [0.9, 0.4, 0.2]),
+    (i64::MAX, "Older", [0.7, 0.2, 0.2]),
+];
+const UNKNOWN_AGE: [f32; 3] = [0.5, 0.5, 0.5];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TreemapColor {
+    Category,
+    Age,
+}
+
+impl TreemapColor {
+    pub const ALL: [TreemapColor; 2] = [TreemapColor::Category, TreemapColor::Age];
+
+    fn legend(&self) -> Vec<(&'static str, [f32; 3])> {
+        match self {
+            TreemapColor::Category => CATEGORY_COLORS.into_iter().chain([("Directory", DIR_TILE)]).collect(),
+            TreemapColor::Age => AGE_COLORS.into_iter().map(|(_, name, color)| (name, color)).chain([("Unknown", UNKNOWN_AGE)]).collect(),
+        }
+    }
+}
+
+impl Display for TreemapColor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            TreemapColor::Category => "By category",
+            TreemapColor::Age => "By age",
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Tile {
+    id: usize,
+    rect: Rectangle,
+    // Whether its children are drawn inside it
+    nested: bool
+}
+
+struct Layout {
+    generation: u64,
+    root: usize,
+    size: Size,
+    color: TreemapColor,
+    tiles: Vec<Tile>
+}
+
+#[derive(Default)]
+struct TreemapState {
+    cache: canvas::Cache,
+    hovering: bool,
+    // Laid out while drawing and kept for hovering and clicking
+    layout: RefCell<Option<Layout>>
+}
+
+struct Treemap<'a> {
+    usage: &'a DiskUsage,
+    generation: u64,
+    root: usize,
+    color: TreemapColor
+}
+
+impl Treemap<'_> {
+    // Lays out again only when the scan, the directory, the size or the colors change
+    fn tiles<'s>(&self, state: &'s TreemapState, size: Size) -> Ref<'s, Vec<Tile>> {
+        let key = (self.generation, self.root, size, self.color);
+        let stale = state.layout.borrow().as_ref().is_none_or(|layout| (layout.generation, layout.root, layout.size, layout.color) != key);
+        if stale {
+            let mut tiles = Vec::new();
+            nest(self.usage, self.root, Rectangle::new(Point::ORIGIN, size), 0, &mut tiles);
+            *state.layout.borrow_mut() = Some(Layout { generation: self.generation, root: self.root, size, color: self.color, tiles });
+            state.cache.clear();
+        }
+        Ref::map(state.layout.borrow(), |layout| &layout.as_ref().unwrap().tiles)
+    }
+
+    // The deepest tile under the cursor, children come after their parents
+    fn hovered(&self, state: &TreemapState, bounds: Rectangle, cursor: mouse::Cursor) -> Option<usize> {
+        let position = cursor.position_in(bounds)?;
+        self.tiles(state, bounds.size()).iter().rev().find(|tile| tile.rect.contains(position)).map(|tile| tile.id)
+    }
+
+    fn fill(&self, id: usize) -> Color {
+        let node = &self.usage.nodes[id];
+        let color = match self.color {
+            TreemapColor::Category if node.childs.is_some() => DIR_TILE,
+            TreemapColor::Category => CATEGORY_COLORS.iter().find(|(name, _)| *name == node.category).map_or(UNKNOWN_AGE, |(_, color)| *color),
+            TreemapColor::Age => match node.modified {
+                None => UNKNOWN_AGE,
+                Some(modified) => {
+                    let days = (self.usage.reference - modified) / 86_400_000_000_000;
+                    AGE_COLORS.iter().find(|(limit, _, _)| days < *limit).map_or(UNKNOWN_AGE, |(_, _, color)| *color)
+                }
+            },
+        };
+        color.into()
+    }
+}
+
+impl canvas::Program<Message> for Treemap<'_> {
+    type State = TreemapState;
+
+    fn update(&self, state: &mut TreemapState, event: &canvas::Event, bounds: Rectangle, cursor: mouse::Cursor) -> Option<canvas::Action<Message>> {
+        match event {
+            // The path and size follow the cursor, and go away with it
+            canvas::Event::Mouse(mouse::Event::CursorMoved { .. }) => {
+                let hovering = cursor.is_over(bounds);
+                let redraw = hovering || state.hovering;
+                state.hovering = hovering;
+                redraw.then(canvas::Action::request_redraw)
+            }
+            // Files drill down to the directory holding them
+            canvas::Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)) => {
+                let id = self.hovered(state, bounds, cursor)?;
+                let node = &self.usage.nodes[id];
+                let dir = if node.childs.is_some() { id } else { node.parent? };
+                Some(canvas::Action::publish(Message::FileViewCurrent(dir)).and_capture())
+            }
+            canvas::Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Right)) if cursor.is_over(bounds) => {
+                let parent = self.usage.nodes[self.root].parent?;
+                Some(canvas::Action::publish(Message::FileViewCurrent(parent)).and_capture())
+            }
+            _ => None,
+        }
+    }
+
+    fn draw(&self, state: &TreemapState, renderer: &Renderer, theme: &Theme, bounds: Rectangle, cursor: mouse::Cursor) -> Vec<canvas::Geometry> {
+        let palette = theme.palette();
+        let tiles = self.tiles(state, bounds.size());
+        let map = state.cache.draw(renderer, bounds.size(), |frame| {
+            for tile in tiles.iter() {
+                let rect = tile.rect;
+                let color = match tile.nested {
+                    true => Color { a: 0.4, ..Color::from(DIR_TILE) },
+                    false => self.fill(tile.id),
+                };
+                frame.fill_rectangle(rect.position(), Size::new((rect.width - 1.).max(1.), (rect.height - 1.).max(1.)), color);
+                if rect.width > 40. && rect.height > HEADER {
+                    frame.fill_text(label(&self.usage.nodes[tile.id].name, Point::new(rect.x + PADDING, rect.y + 1.), rect.width - PADDING, palette.text));
+                }
+            }
+        });
+        let mut overlay = canvas::Frame::new(renderer, bounds.size());
+        if let (Some(id), Some(position)) = (self.hovered(state, bounds, cursor), cursor.position_in(bounds)) {
+            let rect = tiles.iter().find(|tile| tile.id == id).unwrap().rect;
+            overlay.stroke(&canvas::Path::rectangle(rect.position(), rect.size()), canvas::Stroke::default().with_color(palette.text).with_width(2.));
+            hover_box(&mut overlay, self.usage, id, position, palette);
+        }
+        vec![map, overlay.into_geometry()]
+    }
+
+    fn mouse_interaction(&self, state: &TreemapState, bounds: Rectangle, cursor: mouse::Cursor) -> mouse::Interaction {
+        match self.hovered(state, bounds, cursor) {
+            Some(_) => mouse::Interaction::Pointer,
+            None => mouse::Interaction::default(),
+        }
+    }
+}
+
+// About 7 pixels a character, as the text is not clipped
+pub(super) fn label(content: &str, position: Point, width: f32, color: Color) -> canvas::Text {
+    canvas::Text {
+        content: content.chars().take((width / 7.) as usize).collect(),
+        position,
+        color,
+        size: 12.into(),
+        font: FONT,
+        ..Default::default()
+    }
+}
+
+// The path and size of a node next to the cursor, flipping to the other side of it near the edges
+pub(super) fn hover_box(frame: &mut canvas::Frame, usage: &DiskUsage, id: usize, position: Point, palette: Palette) {
+    let node = &usage.nodes[id];
+    let content = match node.childs {
+        Some(_) => format!("{}\n{} in {} items", usage.path(id), size_string(node.size), node.count - 1),
+        None => format!("{}\n{}", usage.path(id), size_string(node.size)),
+    };
+    let width = content.lines().map(|line| line.chars().count()).max().unwrap_or_default() as f32 * 7. + 10.;
+    let bounds = frame.size();
+    let x = if position.x + 15. + width > bounds.width { (position.x - 5. - width).max(0.) } else { position.x + 15. };
+    let y = if position.y + 15. + 40. > bounds.height { (position.y - 45.).max(0.) } else { position.y + 15. };
+    frame.fill_rectangle(Point::new(x, y), Size::new(width, 40.), palette.background);
+    frame.stroke(&canvas::Path::rectangle(Point::new(x, y), Size::new(width, 40.)), canvas::Stroke::default().with_color(palette.text));
+    frame.fill_text(canvas::Text { content, ..label("", Point::new(x + 5., y + 4.), 0., palette.text) });
+}
+
+// Children are laid out inside the directory tile, below a header with its name
+fn nest(usage: &DiskUsage, id: usize, rect: Rectangle, depth: usize, tiles: &mut Vec<Tile>) {
+    let Some(childs) = usage.nodes[id].childs.clone() else {
+        return;
+    };
+    let mut sizes: Vec<_> = childs.map(|child| (child, usage.nodes[child].size)).filter(|(_, size)| *size > 0).collect();
+    sizes.sort_by(|(ida, sizea), (idb, sizeb)| sizeb.cmp(sizea).then(ida.cmp(idb)));
+    let start = tiles.len();
+    squarify(&sizes, rect, tiles);
+    if depth + 1 == DEPTH {
+        return;
+    }
+    for i in start..tiles.len() {
+        let Tile { id, rect, .. } = tiles[i];
+        if usage.nodes[id].childs.is_some() && rect.width >= MIN_NESTED && rect.height >= MIN_NESTED {
+            tiles[i].nested = true;
+            let inner = Rectangle::new(Point::new(rect.x + PADDING, rect.y + HEADER), Size::new(rect.width - 2. * PADDING - 1., rect.height - HEADER - PADDING - 1.));
+            nest(usage, id, inner, depth + 1, tiles);
+        }
+    }
+}
+
+// Squarified treemap by Bruls, Huizing and van Wijk, the sizes are sorted from the largest.
+// A row along the shorter side takes items as long as its worst aspect ratio improves.
+fn squarify(sizes: &[(usize, u64)], mut rect: Rectangle, tiles: &mut Vec<Tile>) {
+    let total: u64 = sizes.iter().map(|(_, size)| size).sum();
+    if total == 0 || rect.width < 1. || rect.height < 1. {
+        return;
+    }
+    let scale = rect.width * rect.height / total as f32;
+    let areas: Vec<f32> = sizes.iter().map(|(_, size)| *size as f32 * scale).collect();
+    // The largest area of a row is its first, the smallest its last
+    let worst = |first: f32, last: f32, sum: f32, side: f32| (side * side * first / (sum * sum)).max(sum * sum / (side * side * last));
+    let mut start = 0;
+    while start < areas.len() {
+        let side = rect.width.min(rect.height);
+        let mut end = start + 1;
+        let mut sum = areas[start];
+        while end < areas.len() && worst(areas[start], areas[end], sum + areas[end], side) <= worst(areas[start], areas[end - 1], sum, side) {
+            sum += areas[end];
+            end += 1;
+        }
+        let thickness = sum / side;
+        let mut offset = 0.;
+        for i in start..end {
+            let length = areas[i] / thickness;
+            let tile = match rect.width >= rect.height {
+                true => Rectangle::new(Point::new(rect.x, rect.y + offset), Size::new(thickness, length)),
+                false => Rectangle::new(Point::new(rect.x + offset, rect.y), Size::new(length, thickness)),
+            };
+            // Tiles below a pixel are not worth drawing or hovering
+            if tile.width >= 1. && tile.height >= 1. {
+                tiles.push(Tile { id: sizes[i].0, rect: tile, nested: false });
+            }
+            offset += length;
+        }
+        match rect.width >= rect.height {
+            true => {
+                rect.x += thickness;
+                rect.width -= thickness;
+            }
+            false => {
+                rect.y += thickness;
+                rect.height -= thickness;
+            }
+        }
+        start = end;
+    }
+}
+
+pub fn treemap_view(state: &State) -> Element<'_> {
+    let Some(usage) = &state.disk_usage else {
+        return Container::new(text("Sizes are not computed")).center(Length::Fill).into();
+    };
+    let root = state.file_view_current;
+    let legend = Row::with_children(state.treemap_color.legend().into_iter().map(|(name, color)| text(format!("■ {}", name)).color(color).into())).spacing(10);
+    let treemap = canvas(Treemap { usage, generation: state.scan_generation, root, color: state.treemap_color }).width(Length::Fill).height(Length::Fill);
+    column![breadcrumb(usage, root), treemap, legend].spacing(5).into()
+}
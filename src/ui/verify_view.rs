@@ -0,0 +1,55 @@
+use super::base::*;
+use super::report_view::{cell, item_link, report_table};
+use crate::analysis::VerifyStatus;
+use iced::widget::{button, column, horizontal_space, row, text, text_input};
+use iced::{Alignment, Length};
+
+const STATUSES: [VerifyStatus; 8] = [
+    VerifyStatus::Ok,
+    VerifyStatus::BitRot,
+    VerifyStatus::Modified,
+    VerifyStatus::Older,
+    VerifyStatus::Missing,
+    VerifyStatus::New,
+    VerifyStatus::TypeChanged,
+    VerifyStatus::Unreadable,
+];
+
+pub fn verify_view(state: &State) -> Element<'_> {
+    let top = row![
+        text("Verify at:").width(80),
+        text_input("Root of the scanned directory", &state.verify_root).on_input(Message::VerifyRoot),
+        button("Verify").on_press(Message::ConfirmVerify)
+    ]
+    .align_y(Alignment::Center);
+    let Some(report) = &state.verify_report else {
+        return column![top, Container::new(text("Re-hash the scanned directory and compare it with the record")).center(Length::Fill)].into();
+    };
+    let summary = row(STATUSES.iter().map(|status| text(format!("{}: {}", status_string(status), report.count(*status))).into())).spacing(15);
+    let rows = report.entries.iter().map(|entry| vec![
+        cell(status_string(&entry.status)),
+        match entry.id {
+            Some(id) => item_link(entry.path.clone(), id),
+            None => cell(&entry.path),
+        },
+    ]);
+    column![
+        top,
+        report_table(&["Status", "Path"], rows),
+        row![summary, horizontal_space(), button("Export report").on_press(Message::ExportVerifyFileDialog)].align_y(Alignment::Center)
+    ]
+    .into()
+}
+
+fn status_string(status: &VerifyStatus) -> &'static str {
+    match status {
+        VerifyStatus::Ok => "OK",
+        VerifyStatus::BitRot => "Changed, same time (bit rot?)",
+        VerifyStatus::Modified => "Changed, newer time",
+        VerifyStatus::Older => "Changed, older time",
+        VerifyStatus::Missing => "Missing",
+        VerifyStatus::New => "New",
+        VerifyStatus::TypeChanged => "Type changed",
+        VerifyStatus::Unreadable => "Unreadable",
+    }
+}